@@ -1,10 +1,17 @@
 #![no_std]
 
+#[cfg(test)]
+extern crate std;
+
 pub mod instructions;
 pub mod state;
 
 pinocchio_pubkey::declare_id!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
 
+/// Mint of the native token (wrapped SOL).
+pub const NATIVE_MINT: pinocchio::pubkey::Pubkey =
+    pinocchio_pubkey::pubkey!("So11111111111111111111111111111111111111112");
+
 use core::mem::MaybeUninit;
 
 const UNINIT_BYTE: MaybeUninit<u8> = MaybeUninit::<u8>::uninit();
@@ -14,13 +14,14 @@ pub enum AccountState {
     Frozen,
 }
 
+/// Values that are not a valid state are treated as `Uninitialized`, since
+/// [`TokenAccount::validate`](crate::state::TokenAccount::validate) rejects them.
 impl From<u8> for AccountState {
     fn from(value: u8) -> Self {
         match value {
-            0 => AccountState::Uninitialized,
             1 => AccountState::Initialized,
             2 => AccountState::Frozen,
-            _ => panic!("invalid account state value: {value}"),
+            _ => AccountState::Uninitialized,
         }
     }
 }
@@ -1,7 +1,9 @@
 use pinocchio::{
-    account_info::{AccountInfo, Ref},
+    account_info::{AccountData, AccountInfo, Ref, RefMut},
+    coption::COption,
     program_error::ProgramError,
     pubkey::Pubkey,
+    ProgramResult,
 };
 
 use crate::ID;
@@ -9,14 +11,11 @@ use crate::ID;
 /// Mint data.
 #[repr(C)]
 pub struct Mint {
-    /// Indicates whether the mint authority is present or not.
-    mint_authority_flag: [u8; 4],
-
     /// Optional authority used to mint new tokens. The mint authority may only
     /// be provided during mint creation. If no mint authority is present
     /// then the mint has a fixed supply and no further tokens may be
     /// minted.
-    mint_authority: Pubkey,
+    mint_authority: COption<Pubkey>,
 
     /// Total supply of tokens.
     supply: [u8; 8],
@@ -27,32 +26,70 @@ pub struct Mint {
     /// Is `true` if this structure has been initialized.
     is_initialized: u8,
 
-    /// Indicates whether the freeze authority is present or not.
-    freeze_authority_flag: [u8; 4],
-
     /// Optional authority to freeze token accounts.
-    freeze_authority: Pubkey,
+    freeze_authority: COption<Pubkey>,
+}
+
+unsafe impl AccountData for Mint {
+    const OWNER: Pubkey = ID;
 }
 
 impl Mint {
     /// The length of the `Mint` account data.
     pub const LEN: usize = core::mem::size_of::<Mint>();
 
+    /// Initialize a `Mint` in the given bytes.
+    ///
+    /// This method writes the SPL Token layout of an initialized mint with no supply,
+    /// failing if `bytes` does not have the length of a `Mint` or already contains an
+    /// initialized mint.
+    pub fn init<'a>(
+        bytes: &'a mut [u8],
+        decimals: u8,
+        mint_authority: Option<&Pubkey>,
+        freeze_authority: Option<&Pubkey>,
+    ) -> Result<&'a mut Self, ProgramError> {
+        if bytes.len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        // SAFETY: `Mint` has an alignment of 1 and any bytes are a valid representation.
+        let mint = unsafe { Self::from_bytes_mut(bytes) };
+
+        if mint.is_initialized() {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+
+        mint.set_mint_authority(mint_authority);
+        mint.set_supply(0);
+        mint.decimals = decimals;
+        mint.is_initialized = 1;
+        mint.set_freeze_authority(freeze_authority);
+
+        Ok(mint)
+    }
+
+    /// Check that the `Mint` is initialized and has a valid SPL Token layout.
+    pub fn validate(&self) -> ProgramResult {
+        match self.is_initialized {
+            0 => return Err(ProgramError::UninitializedAccount),
+            1 => (),
+            _ => return Err(ProgramError::InvalidAccountData),
+        }
+
+        if !(self.mint_authority.is_valid() && self.freeze_authority.is_valid()) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(())
+    }
+
     /// Return a `Mint` from the given account info.
     ///
     /// This method performs owner and length validation on `AccountInfo`, safe borrowing
     /// the account data.
     #[inline]
-    pub fn from_account_info(account_info: &AccountInfo) -> Result<Ref<Mint>, ProgramError> {
-        if account_info.data_len() != Self::LEN {
-            return Err(ProgramError::InvalidAccountData);
-        }
-        if account_info.owner() != &ID {
-            return Err(ProgramError::InvalidAccountOwner);
-        }
-        Ok(Ref::map(account_info.try_borrow_data()?, |data| unsafe {
-            Self::from_bytes(data)
-        }))
+    pub fn from_account_info(account_info: &AccountInfo) -> Result<Ref<'_, Mint>, ProgramError> {
+        account_info.load::<Self>()
     }
 
     /// Return a `Mint` from the given account info.
@@ -68,13 +105,35 @@ impl Mint {
     pub unsafe fn from_account_info_unchecked(
         account_info: &AccountInfo,
     ) -> Result<&Self, ProgramError> {
-        if account_info.data_len() != Self::LEN {
-            return Err(ProgramError::InvalidAccountData);
-        }
-        if account_info.owner() != &ID {
-            return Err(ProgramError::InvalidAccountOwner);
-        }
-        Ok(Self::from_bytes(account_info.borrow_data_unchecked()))
+        account_info.load_unchecked::<Self>()
+    }
+
+    /// Return a mutable `Mint` from the given account info.
+    ///
+    /// This method performs owner and length validation on `AccountInfo`, safe borrowing
+    /// the account data.
+    #[inline]
+    pub fn from_account_info_mut(
+        account_info: &AccountInfo,
+    ) -> Result<RefMut<'_, Mint>, ProgramError> {
+        account_info.load_mut::<Self>()
+    }
+
+    /// Return a mutable `Mint` from the given account info.
+    ///
+    /// This method performs owner and length validation on `AccountInfo`, but does not
+    /// perform the borrow check.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that it is safe to mutably borrow the account data – e.g., there
+    /// are no active borrows of the account data.
+    #[inline]
+    #[allow(clippy::mut_from_ref)]
+    pub unsafe fn from_account_info_mut_unchecked(
+        account_info: &AccountInfo,
+    ) -> Result<&mut Self, ProgramError> {
+        account_info.load_mut_unchecked::<Self>()
     }
 
     /// Return a `Mint` from the given bytes.
@@ -87,17 +146,23 @@ impl Mint {
         &*(bytes.as_ptr() as *const Mint)
     }
 
+    /// Return a mutable `Mint` from the given bytes.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that `bytes` contains a valid representation of `Mint`.
+    #[inline(always)]
+    pub unsafe fn from_bytes_mut(bytes: &mut [u8]) -> &mut Self {
+        &mut *(bytes.as_mut_ptr() as *mut Mint)
+    }
+
     #[inline(always)]
     pub fn has_mint_authority(&self) -> bool {
-        self.mint_authority_flag[0] == 1
+        self.mint_authority.is_some()
     }
 
     pub fn mint_authority(&self) -> Option<&Pubkey> {
-        if self.has_mint_authority() {
-            Some(self.mint_authority_unchecked())
-        } else {
-            None
-        }
+        self.mint_authority.as_ref()
     }
 
     /// Return the mint authority.
@@ -106,13 +171,23 @@ impl Mint {
     /// authority set since it skips the `Option` check.
     #[inline(always)]
     pub fn mint_authority_unchecked(&self) -> &Pubkey {
-        &self.mint_authority
+        self.mint_authority.as_ref_unchecked()
+    }
+
+    #[inline(always)]
+    pub fn set_mint_authority(&mut self, mint_authority: Option<&Pubkey>) {
+        self.mint_authority.set(mint_authority.copied());
     }
 
     pub fn supply(&self) -> u64 {
         unsafe { core::ptr::read_unaligned(self.supply.as_ptr() as *const u64) }
     }
 
+    #[inline(always)]
+    pub fn set_supply(&mut self, supply: u64) {
+        self.supply = supply.to_le_bytes();
+    }
+
     pub fn decimals(&self) -> u8 {
         self.decimals
     }
@@ -123,15 +198,11 @@ impl Mint {
 
     #[inline(always)]
     pub fn has_freeze_authority(&self) -> bool {
-        self.freeze_authority_flag[0] == 1
+        self.freeze_authority.is_some()
     }
 
     pub fn freeze_authority(&self) -> Option<&Pubkey> {
-        if self.has_freeze_authority() {
-            Some(self.freeze_authority_unchecked())
-        } else {
-            None
-        }
+        self.freeze_authority.as_ref()
     }
 
     /// Return the freeze authority.
@@ -140,6 +211,62 @@ impl Mint {
     /// authority set since it skips the `Option` check.
     #[inline(always)]
     pub fn freeze_authority_unchecked(&self) -> &Pubkey {
-        &self.freeze_authority
+        self.freeze_authority.as_ref_unchecked()
+    }
+
+    #[inline(always)]
+    pub fn set_freeze_authority(&mut self, freeze_authority: Option<&Pubkey>) {
+        self.freeze_authority.set(freeze_authority.copied());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_init() {
+        let mut bytes = [0u8; Mint::LEN];
+
+        let mint = Mint::init(&mut bytes, 6, Some(&[1; 32]), None).unwrap();
+        assert_eq!(mint.validate(), Ok(()));
+        assert_eq!(mint.mint_authority(), Some(&[1; 32]));
+        assert_eq!(mint.freeze_authority(), None);
+        assert_eq!(mint.decimals(), 6);
+        assert_eq!(mint.supply(), 0);
+
+        // SPL Token layout
+        assert_eq!(bytes[..4], [1, 0, 0, 0]);
+        assert_eq!(bytes[4..36], [1; 32]);
+        assert_eq!(bytes[36..44], [0; 8]);
+        assert_eq!(bytes[44..46], [6, 1]);
+        assert_eq!(bytes[46..], [0; 36]);
+
+        assert_eq!(
+            Mint::init(&mut bytes, 6, None, None).err(),
+            Some(ProgramError::AccountAlreadyInitialized)
+        );
+        assert_eq!(
+            Mint::init(&mut [0; Mint::LEN - 1], 6, None, None).err(),
+            Some(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut bytes = [0u8; Mint::LEN];
+
+        let mint = unsafe { Mint::from_bytes(&bytes) };
+        assert_eq!(mint.validate(), Err(ProgramError::UninitializedAccount));
+
+        Mint::init(&mut bytes, 9, None, Some(&[2; 32])).unwrap();
+
+        for (offset, value) in [(45, 2), (0, 2), (46, 2)] {
+            let mut invalid = bytes;
+            invalid[offset] = value;
+
+            let mint = unsafe { Mint::from_bytes(&invalid) };
+            assert_eq!(mint.validate(), Err(ProgramError::InvalidAccountData));
+        }
     }
 }
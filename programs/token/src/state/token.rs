@@ -1,11 +1,13 @@
 use super::AccountState;
 use pinocchio::{
-    account_info::{AccountInfo, Ref},
+    account_info::{AccountData, AccountInfo, Ref, RefMut},
+    coption::COption,
     program_error::ProgramError,
     pubkey::Pubkey,
+    ProgramResult,
 };
 
-use crate::ID;
+use crate::{ID, NATIVE_MINT};
 
 /// Token account data.
 #[repr(C)]
@@ -19,38 +21,103 @@ pub struct TokenAccount {
     /// The amount of tokens this account holds.
     amount: [u8; 8],
 
-    /// Indicates whether the delegate is present or not.
-    delegate_flag: [u8; 4],
-
     /// If `delegate` is `Some` then `delegated_amount` represents
     /// the amount authorized by the delegate.
-    delegate: Pubkey,
+    delegate: COption<Pubkey>,
 
     /// The account's state.
     state: u8,
 
-    /// Indicates whether this account represents a native token or not.
-    is_native: [u8; 4],
-
     /// If is_native.is_some, this is a native token, and the value logs the
     /// rent-exempt reserve. An Account is required to be rent-exempt, so
     /// the value is used by the Processor to ensure that wrapped SOL
     /// accounts do not drop below this threshold.
-    native_amount: [u8; 8],
+    is_native: COption<u64>,
 
     /// The amount delegated.
     delegated_amount: [u8; 8],
 
-    /// Indicates whether the close authority is present or not.
-    close_authority_flag: [u8; 4],
-
     /// Optional authority to close the account.
-    close_authority: Pubkey,
+    close_authority: COption<Pubkey>,
+}
+
+unsafe impl AccountData for TokenAccount {
+    const OWNER: Pubkey = ID;
 }
 
 impl TokenAccount {
     pub const LEN: usize = core::mem::size_of::<TokenAccount>();
 
+    /// Initialize a `TokenAccount` in the given bytes.
+    ///
+    /// This method writes the SPL Token layout of an initialized account without delegate
+    /// and close authority, failing if `bytes` does not have the length of a `TokenAccount`
+    /// or already contains an initialized account. The `native_amount` (the rent-exempt
+    /// reserve) must be specified if and only if `mint` is the [`NATIVE_MINT`].
+    pub fn init<'a>(
+        bytes: &'a mut [u8],
+        mint: &Pubkey,
+        owner: &Pubkey,
+        amount: u64,
+        native_amount: Option<u64>,
+    ) -> Result<&'a mut Self, ProgramError> {
+        if bytes.len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if native_amount.is_some() != (mint == &NATIVE_MINT) {
+            return Err(ProgramError::InvalidArgument);
+        }
+        // SAFETY: `TokenAccount` has an alignment of 1 and any bytes are a valid
+        // representation.
+        let account = unsafe { Self::from_bytes_mut(bytes) };
+
+        if account.state != AccountState::Uninitialized as u8 {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+
+        account.mint = *mint;
+        account.set_owner(owner);
+        account.set_amount(amount);
+        account.set_delegate(None);
+        account.set_state(AccountState::Initialized);
+        account.is_native.set(native_amount);
+        account.set_delegated_amount(0);
+        account.set_close_authority(None);
+
+        Ok(account)
+    }
+
+    /// Check that the `TokenAccount` is initialized and has a valid SPL Token layout.
+    ///
+    /// Besides the encoding of the fields, this checks that only accounts of the
+    /// [`NATIVE_MINT`] are native and that there is no delegated amount without a
+    /// delegate.
+    pub fn validate(&self) -> ProgramResult {
+        if self.state == AccountState::Uninitialized as u8 {
+            return Err(ProgramError::UninitializedAccount);
+        }
+        if self.state > AccountState::Frozen as u8 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if !(self.delegate.is_valid()
+            && self.is_native.is_valid()
+            && self.close_authority.is_valid())
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if self.is_native() != (self.mint == NATIVE_MINT) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if !self.has_delegate() && self.delegated_amount() != 0 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(())
+    }
+
     /// Return a `TokenAccount` from the given account info.
     ///
     /// This method performs owner and length validation on `AccountInfo`, safe borrowing
@@ -58,16 +125,8 @@ impl TokenAccount {
     #[inline]
     pub fn from_account_info(
         account_info: &AccountInfo,
-    ) -> Result<Ref<TokenAccount>, ProgramError> {
-        if account_info.data_len() != Self::LEN {
-            return Err(ProgramError::InvalidAccountData);
-        }
-        if account_info.owner() != &ID {
-            return Err(ProgramError::InvalidAccountData);
-        }
-        Ok(Ref::map(account_info.try_borrow_data()?, |data| unsafe {
-            Self::from_bytes(data)
-        }))
+    ) -> Result<Ref<'_, TokenAccount>, ProgramError> {
+        account_info.load::<Self>()
     }
 
     /// Return a `TokenAccount` from the given account info.
@@ -83,13 +142,35 @@ impl TokenAccount {
     pub unsafe fn from_account_info_unchecked(
         account_info: &AccountInfo,
     ) -> Result<&TokenAccount, ProgramError> {
-        if account_info.data_len() != Self::LEN {
-            return Err(ProgramError::InvalidAccountData);
-        }
-        if account_info.owner() != &ID {
-            return Err(ProgramError::InvalidAccountData);
-        }
-        Ok(Self::from_bytes(account_info.borrow_data_unchecked()))
+        account_info.load_unchecked::<Self>()
+    }
+
+    /// Return a mutable `TokenAccount` from the given account info.
+    ///
+    /// This method performs owner and length validation on `AccountInfo`, safe borrowing
+    /// the account data.
+    #[inline]
+    pub fn from_account_info_mut(
+        account_info: &AccountInfo,
+    ) -> Result<RefMut<'_, TokenAccount>, ProgramError> {
+        account_info.load_mut::<Self>()
+    }
+
+    /// Return a mutable `TokenAccount` from the given account info.
+    ///
+    /// This method performs owner and length validation on `AccountInfo`, but does not
+    /// perform the borrow check.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that it is safe to mutably borrow the account data – e.g., there
+    /// are no active borrows of the account data.
+    #[inline]
+    #[allow(clippy::mut_from_ref)]
+    pub unsafe fn from_account_info_mut_unchecked(
+        account_info: &AccountInfo,
+    ) -> Result<&mut TokenAccount, ProgramError> {
+        account_info.load_mut_unchecked::<Self>()
     }
 
     /// Return a `TokenAccount` from the given bytes.
@@ -102,6 +183,16 @@ impl TokenAccount {
         &*(bytes.as_ptr() as *const TokenAccount)
     }
 
+    /// Return a mutable `TokenAccount` from the given bytes.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that `bytes` contains a valid representation of `TokenAccount`.
+    #[inline(always)]
+    pub unsafe fn from_bytes_mut(bytes: &mut [u8]) -> &mut Self {
+        &mut *(bytes.as_mut_ptr() as *mut TokenAccount)
+    }
+
     pub fn mint(&self) -> &Pubkey {
         &self.mint
     }
@@ -110,45 +201,61 @@ impl TokenAccount {
         &self.owner
     }
 
+    #[inline(always)]
+    pub fn set_owner(&mut self, owner: &Pubkey) {
+        self.owner = *owner;
+    }
+
     pub fn amount(&self) -> u64 {
         unsafe { core::ptr::read_unaligned(self.amount.as_ptr() as *const u64) }
     }
 
+    #[inline(always)]
+    pub fn set_amount(&mut self, amount: u64) {
+        self.amount = amount.to_le_bytes();
+    }
+
     #[inline(always)]
     pub fn has_delegate(&self) -> bool {
-        self.delegate_flag[0] == 1
+        self.delegate.is_some()
     }
 
     pub fn delegate(&self) -> Option<&Pubkey> {
-        if self.has_delegate() {
-            Some(self.delegate_unchecked())
-        } else {
-            None
-        }
+        self.delegate.as_ref()
     }
 
     /// Use this when you know the account will have a delegate and want to skip the `Option` check.
     #[inline(always)]
     pub fn delegate_unchecked(&self) -> &Pubkey {
-        &self.delegate
+        self.delegate.as_ref_unchecked()
+    }
+
+    #[inline(always)]
+    pub fn set_delegate(&mut self, delegate: Option<&Pubkey>) {
+        self.delegate.set(delegate.copied());
     }
 
+    /// Return the state of the account.
+    ///
+    /// Invalid state values, which [`TokenAccount::validate`] rejects, are
+    /// reported as [`AccountState::Uninitialized`].
     #[inline(always)]
     pub fn state(&self) -> AccountState {
         self.state.into()
     }
 
+    #[inline(always)]
+    pub fn set_state(&mut self, state: AccountState) {
+        self.state = state.into();
+    }
+
     #[inline(always)]
     pub fn is_native(&self) -> bool {
-        self.is_native[0] == 1
+        self.is_native.is_some()
     }
 
     pub fn native_amount(&self) -> Option<u64> {
-        if self.is_native() {
-            Some(self.native_amount_unchecked())
-        } else {
-            None
-        }
+        self.is_native.as_option()
     }
 
     /// Return the native amount.
@@ -157,24 +264,25 @@ impl TokenAccount {
     /// skips the `Option` check.
     #[inline(always)]
     pub fn native_amount_unchecked(&self) -> u64 {
-        unsafe { core::ptr::read_unaligned(self.native_amount.as_ptr() as *const u64) }
+        self.is_native.value_unchecked()
     }
 
     pub fn delegated_amount(&self) -> u64 {
         unsafe { core::ptr::read_unaligned(self.delegated_amount.as_ptr() as *const u64) }
     }
 
+    #[inline(always)]
+    pub fn set_delegated_amount(&mut self, delegated_amount: u64) {
+        self.delegated_amount = delegated_amount.to_le_bytes();
+    }
+
     #[inline(always)]
     pub fn has_close_authority(&self) -> bool {
-        self.close_authority_flag[0] == 1
+        self.close_authority.is_some()
     }
 
     pub fn close_authority(&self) -> Option<&Pubkey> {
-        if self.has_close_authority() {
-            Some(self.close_authority_unchecked())
-        } else {
-            None
-        }
+        self.close_authority.as_ref()
     }
 
     /// Return the close authority.
@@ -183,7 +291,12 @@ impl TokenAccount {
     /// authority set since it skips the `Option` check.
     #[inline(always)]
     pub fn close_authority_unchecked(&self) -> &Pubkey {
-        &self.close_authority
+        self.close_authority.as_ref_unchecked()
+    }
+
+    #[inline(always)]
+    pub fn set_close_authority(&mut self, close_authority: Option<&Pubkey>) {
+        self.close_authority.set(close_authority.copied());
     }
 
     #[inline(always)]
@@ -196,3 +309,139 @@ impl TokenAccount {
         self.state == AccountState::Frozen as u8
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_init() {
+        let mut bytes = [0u8; TokenAccount::LEN];
+
+        let account = TokenAccount::init(&mut bytes, &[1; 32], &[2; 32], 100, None).unwrap();
+        assert_eq!(account.validate(), Ok(()));
+        assert_eq!(account.mint(), &[1; 32]);
+        assert_eq!(account.owner(), &[2; 32]);
+        assert_eq!(account.amount(), 100);
+        assert_eq!(account.state(), AccountState::Initialized);
+        assert_eq!(account.native_amount(), None);
+
+        // SPL Token layout
+        assert_eq!(bytes[..32], [1; 32]);
+        assert_eq!(bytes[32..64], [2; 32]);
+        assert_eq!(bytes[64..72], 100u64.to_le_bytes());
+        assert_eq!(bytes[72..108], [0; 36]);
+        assert_eq!(bytes[108], 1);
+        assert_eq!(bytes[109..], [0; 56]);
+
+        assert_eq!(
+            TokenAccount::init(&mut bytes, &[1; 32], &[2; 32], 0, None).err(),
+            Some(ProgramError::AccountAlreadyInitialized)
+        );
+
+        let mut bytes = [0u8; TokenAccount::LEN];
+        assert_eq!(
+            TokenAccount::init(&mut bytes, &[1; 32], &[2; 32], 0, Some(10)).err(),
+            Some(ProgramError::InvalidArgument)
+        );
+
+        let account = TokenAccount::init(&mut bytes, &NATIVE_MINT, &[2; 32], 0, Some(10)).unwrap();
+        assert_eq!(account.validate(), Ok(()));
+        assert_eq!(account.native_amount(), Some(10));
+        assert_eq!(bytes[109..113], [1, 0, 0, 0]);
+        assert_eq!(bytes[113..121], 10u64.to_le_bytes());
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut bytes = [0u8; TokenAccount::LEN];
+
+        let account = unsafe { TokenAccount::from_bytes(&bytes) };
+        assert_eq!(account.validate(), Err(ProgramError::UninitializedAccount));
+
+        TokenAccount::init(&mut bytes, &[1; 32], &[2; 32], 0, None).unwrap();
+
+        let invalid: [fn(&mut [u8; TokenAccount::LEN]); 4] = [
+            // account state out of range
+            |bytes| bytes[108] = 3,
+            // native account of a non-native mint
+            |bytes| bytes[109] = 1,
+            // delegated amount without a delegate
+            |bytes| bytes[121] = 1,
+            // invalid close authority tag
+            |bytes| bytes[129] = 2,
+        ];
+
+        for update in invalid {
+            let mut bytes = bytes;
+            update(&mut bytes);
+
+            let account = unsafe { TokenAccount::from_bytes(&bytes) };
+            assert_eq!(account.validate(), Err(ProgramError::InvalidAccountData));
+        }
+
+        let account = unsafe { TokenAccount::from_bytes_mut(&mut bytes) };
+        account.set_delegate(Some(&[3; 32]));
+        account.set_delegated_amount(10);
+        account.set_state(AccountState::Frozen);
+        assert_eq!(account.validate(), Ok(()));
+        assert_eq!(account.state(), AccountState::Frozen);
+
+        bytes[108] = 3;
+        let account = unsafe { TokenAccount::from_bytes(&bytes) };
+        assert_eq!(account.state(), AccountState::Uninitialized);
+        assert_eq!(account.validate(), Err(ProgramError::InvalidAccountData));
+    }
+
+    #[test]
+    fn test_from_account_info() {
+        use pinocchio::testing::{InputBuilder, MockAccount};
+
+        use crate::state::Mint;
+
+        let mut token = std::vec![0u8; TokenAccount::LEN];
+        TokenAccount::init(&mut token, &[1; 32], &[2; 32], 10, None).unwrap();
+
+        let mut mint = std::vec![0u8; Mint::LEN];
+        Mint::init(&mut mint, 6, None, None).unwrap();
+
+        let mut input = InputBuilder::new(ID)
+            .account(MockAccount::new([3; 32], ID, 0, token.clone()).writable())
+            .account(MockAccount::new([4; 32], ID, 0, mint.clone()).writable())
+            .account(MockAccount::new([5; 32], [9; 32], 0, token).writable())
+            .account(MockAccount::new([6; 32], [9; 32], 0, mint).writable())
+            .account(MockAccount::new([7; 32], ID, 0, std::vec![0; 10]).writable())
+            .build();
+
+        input
+            .process(|_, accounts, _| {
+                TokenAccount::from_account_info_mut(&accounts[0])?.set_amount(20);
+                assert_eq!(TokenAccount::from_account_info(&accounts[0])?.amount(), 20);
+
+                Mint::from_account_info_mut(&accounts[1])?.set_supply(20);
+                assert_eq!(Mint::from_account_info(&accounts[1])?.supply(), 20);
+
+                // both types report the same errors
+                for error in [
+                    TokenAccount::from_account_info(&accounts[2]).err(),
+                    TokenAccount::from_account_info_mut(&accounts[2]).err(),
+                    unsafe { TokenAccount::from_account_info_unchecked(&accounts[2]) }.err(),
+                    Mint::from_account_info(&accounts[3]).err(),
+                    Mint::from_account_info_mut(&accounts[3]).err(),
+                    unsafe { Mint::from_account_info_unchecked(&accounts[3]) }.err(),
+                ] {
+                    assert_eq!(error, Some(ProgramError::InvalidAccountOwner));
+                }
+
+                for error in [
+                    TokenAccount::from_account_info_mut(&accounts[4]).err(),
+                    Mint::from_account_info_mut(&accounts[4]).err(),
+                ] {
+                    assert_eq!(error, Some(ProgramError::InvalidAccountData));
+                }
+
+                Ok(())
+            })
+            .unwrap();
+    }
+}
@@ -1,5 +1,10 @@
 //! Data structures to represent account information.
-use core::{marker::PhantomData, mem::ManuallyDrop, ptr::NonNull, slice::from_raw_parts_mut};
+use core::{
+    marker::PhantomData,
+    mem::{align_of, size_of, ManuallyDrop},
+    ptr::NonNull,
+    slice::from_raw_parts_mut,
+};
 
 #[cfg(target_os = "solana")]
 use crate::syscalls::sol_memset_;
@@ -20,6 +25,144 @@ macro_rules! get_account_info {
     }};
 }
 
+/// Declare a struct holding the accounts of an instruction.
+///
+/// The struct has a named field for each account and implements
+/// `TryFrom<&[AccountInfo]>`, which assigns the accounts in the order of the
+/// fields and checks the constraints of each field. Additional accounts are
+/// ignored. The conversion does not copy any account nor allocate.
+///
+/// The constraints are specified as a list after the field name:
+///
+/// | Constraint   | Error if not satisfied                     |
+/// |--------------|--------------------------------------------|
+/// | `signer`     | [`ProgramError::MissingRequiredSignature`] |
+/// | `writable`   | [`ProgramError::Immutable`]                |
+/// | `executable` | [`ProgramError::IncorrectProgramId`]       |
+/// | `owner = X`  | [`ProgramError::InvalidAccountOwner`]      |
+/// | `key = X`    | [`ProgramError::InvalidArgument`]          |
+///
+/// If there are fewer accounts than fields, the conversion returns
+/// [`ProgramError::NotEnoughAccountKeys`].
+///
+/// # Example
+///
+/// ```
+/// use pinocchio::{accounts, account_info::AccountInfo, ProgramResult};
+///
+/// const TOKEN_PROGRAM_ID: [u8; 32] = [1; 32];
+///
+/// accounts! {
+///     /// Accounts of the transfer instruction.
+///     pub struct Transfer<'a> {
+///         /// Source token account.
+///         pub source: [writable, owner = TOKEN_PROGRAM_ID],
+///         /// Destination token account.
+///         pub destination: [writable, owner = TOKEN_PROGRAM_ID],
+///         /// Owner of the source account.
+///         pub authority: [signer],
+///         /// The token program.
+///         pub token_program: [key = TOKEN_PROGRAM_ID, executable],
+///     }
+/// }
+///
+/// fn process_transfer(accounts: &[AccountInfo]) -> ProgramResult {
+///     let Transfer { source, destination, .. } = Transfer::try_from(accounts)?;
+///     // ...
+///     Ok(())
+/// }
+/// ```
+///
+/// [`ProgramError::MissingRequiredSignature`]: crate::program_error::ProgramError::MissingRequiredSignature
+/// [`ProgramError::Immutable`]: crate::program_error::ProgramError::Immutable
+/// [`ProgramError::IncorrectProgramId`]: crate::program_error::ProgramError::IncorrectProgramId
+/// [`ProgramError::InvalidAccountOwner`]: crate::program_error::ProgramError::InvalidAccountOwner
+/// [`ProgramError::InvalidArgument`]: crate::program_error::ProgramError::InvalidArgument
+/// [`ProgramError::NotEnoughAccountKeys`]: crate::program_error::ProgramError::NotEnoughAccountKeys
+#[macro_export]
+macro_rules! accounts {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident<$lifetime:lifetime> {
+            $(
+                $(#[$field_meta:meta])*
+                $field_vis:vis $field:ident
+                $( : [ $( $constraint:ident $( = $value:expr )? ),* $(,)? ] )?
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $name<$lifetime> {
+            $(
+                $(#[$field_meta])*
+                $field_vis $field: &$lifetime $crate::account_info::AccountInfo,
+            )*
+        }
+
+        impl $name<'_> {
+            /// Number of accounts expected by the instruction.
+            pub const LEN: usize = [$(stringify!($field)),*].len();
+        }
+
+        impl<$lifetime> TryFrom<&$lifetime [$crate::account_info::AccountInfo]> for $name<$lifetime> {
+            type Error = $crate::program_error::ProgramError;
+
+            #[inline(always)]
+            fn try_from(
+                accounts: &$lifetime [$crate::account_info::AccountInfo],
+            ) -> Result<Self, Self::Error> {
+                let [$($field,)* ..] = accounts else {
+                    return Err($crate::program_error::ProgramError::NotEnoughAccountKeys);
+                };
+
+                $($($(
+                    $crate::__account_constraint!($field, $constraint $(, $value)?);
+                )*)?)*
+
+                Ok(Self { $($field),* })
+            }
+        }
+    };
+}
+
+/// Check a constraint of the [`accounts!`] macro.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __account_constraint {
+    ( $account:ident, signer ) => {
+        if !$account.is_signer() {
+            return Err($crate::program_error::ProgramError::MissingRequiredSignature);
+        }
+    };
+    ( $account:ident, writable ) => {
+        if !$account.is_writable() {
+            return Err($crate::program_error::ProgramError::Immutable);
+        }
+    };
+    ( $account:ident, executable ) => {
+        if !$account.executable() {
+            return Err($crate::program_error::ProgramError::IncorrectProgramId);
+        }
+    };
+    ( $account:ident, owner, $owner:expr ) => {
+        if $account.owner() != &$owner {
+            return Err($crate::program_error::ProgramError::InvalidAccountOwner);
+        }
+    };
+    ( $account:ident, key, $key:expr ) => {
+        if $account.key() != &$key {
+            return Err($crate::program_error::ProgramError::InvalidArgument);
+        }
+    };
+    ( $account:ident, $constraint:ident $(, $value:expr)? ) => {
+        compile_error!(concat!(
+            "unknown account constraint `",
+            stringify!($constraint),
+            "`"
+        ));
+    };
+}
+
 /// Raw account data.
 ///
 /// This data is wrapped in an `AccountInfo` struct, which provides safe access
@@ -80,13 +223,13 @@ pub(crate) struct Account {
 /// be greater than 10_000_000 bytes, so we can use the most significant bit
 /// as a flag to indicate that the original data length has been set and lazily
 /// initialize its value.
-const SET_LEN_MASK: u32 = 1 << 31;
+pub(crate) const SET_LEN_MASK: u32 = 1 << 31;
 
 /// Mask to retrieve the original data length.
 ///
 /// This mask is used to retrieve the original data length from the `original_data_len`
 /// by clearing the flag that indicates the original data length has been set.
-const GET_LEN_MASK: u32 = !SET_LEN_MASK;
+pub(crate) const GET_LEN_MASK: u32 = !SET_LEN_MASK;
 
 /// Wrapper struct for an `Account`.
 ///
@@ -207,7 +350,7 @@ impl AccountInfo {
 
     /// Tries to get a read-only reference to the lamport field, failing if the
     /// field is already mutable borrowed or if 7 borrows already exist.
-    pub fn try_borrow_lamports(&self) -> Result<Ref<u64>, ProgramError> {
+    pub fn try_borrow_lamports(&self) -> Result<Ref<'_, u64>, ProgramError> {
         let borrow_state = unsafe { &mut (*self.raw).borrow_state };
 
         // check if mutable borrow is already taken
@@ -234,7 +377,7 @@ impl AccountInfo {
 
     /// Tries to get a read only reference to the lamport field, failing if the field
     /// is already borrowed in any form.
-    pub fn try_borrow_mut_lamports(&self) -> Result<RefMut<u64>, ProgramError> {
+    pub fn try_borrow_mut_lamports(&self) -> Result<RefMut<'_, u64>, ProgramError> {
         let borrow_state = unsafe { &mut (*self.raw).borrow_state };
 
         // check if any borrow (mutable or immutable) is already taken for lamports
@@ -256,7 +399,7 @@ impl AccountInfo {
 
     /// Tries to get a read only reference to the data field, failing if the field
     /// is already mutable borrowed or if 7 borrows already exist.
-    pub fn try_borrow_data(&self) -> Result<Ref<[u8]>, ProgramError> {
+    pub fn try_borrow_data(&self) -> Result<Ref<'_, [u8]>, ProgramError> {
         let borrow_state = unsafe { &mut (*self.raw).borrow_state };
 
         // check if mutable data borrow is already taken (most significant bit
@@ -289,7 +432,7 @@ impl AccountInfo {
 
     /// Tries to get a read only reference to the data field, failing if the field
     /// is already borrowed in any form.
-    pub fn try_borrow_mut_data(&self) -> Result<RefMut<[u8]>, ProgramError> {
+    pub fn try_borrow_mut_data(&self) -> Result<RefMut<'_, [u8]>, ProgramError> {
         let borrow_state = unsafe { &mut (*self.raw).borrow_state };
 
         // check if any borrow (mutable or immutable) is already taken for data
@@ -309,6 +452,93 @@ impl AccountInfo {
         })
     }
 
+    /// Tries to get a read only reference to the account data as a `T`, failing if
+    /// the data is already mutable borrowed or if it does not hold a `T`.
+    ///
+    /// The account must be owned by [`AccountData::OWNER`] and its data must be
+    /// the [`AccountData::DISCRIMINATOR`] followed by [`AccountData::LEN`] bytes
+    /// properly aligned for `T`.
+    pub fn load<T: AccountData>(&self) -> Result<Ref<'_, T>, ProgramError> {
+        self.check_account_data::<T>()?;
+
+        let data = self.try_borrow_data()?;
+        check_data_layout::<T>(&data)?;
+
+        Ok(Ref::map(data, |data| unsafe {
+            &*(data.as_ptr().add(T::DISCRIMINATOR.len()) as *const T)
+        }))
+    }
+
+    /// Tries to get a mutable reference to the account data as a `T`, failing if
+    /// the data is already borrowed in any form or if it does not hold a `T`.
+    ///
+    /// The same checks as [`AccountInfo::load`] are performed.
+    pub fn load_mut<T: AccountData>(&self) -> Result<RefMut<'_, T>, ProgramError> {
+        self.check_account_data::<T>()?;
+
+        let data = self.try_borrow_mut_data()?;
+        check_data_layout::<T>(&data)?;
+
+        Ok(RefMut::map(data, |data| unsafe {
+            &mut *(data.as_mut_ptr().add(T::DISCRIMINATOR.len()) as *mut T)
+        }))
+    }
+
+    /// Returns a read only reference to the account data as a `T` without
+    /// checking the borrow state, failing if the data does not hold a `T`.
+    ///
+    /// The same checks as [`AccountInfo::load`] are performed.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that it is safe to borrow the account data – e.g., there are
+    /// no mutable borrows of the account data.
+    #[inline]
+    pub unsafe fn load_unchecked<T: AccountData>(&self) -> Result<&T, ProgramError> {
+        self.check_account_data::<T>()?;
+
+        let data = self.borrow_data_unchecked();
+        check_data_layout::<T>(data)?;
+
+        Ok(&*(data.as_ptr().add(T::DISCRIMINATOR.len()) as *const T))
+    }
+
+    /// Returns a mutable reference to the account data as a `T` without
+    /// checking the borrow state, failing if the data does not hold a `T`.
+    ///
+    /// The same checks as [`AccountInfo::load`] are performed.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that it is safe to mutably borrow the account data – e.g., there
+    /// are no active borrows of the account data.
+    #[inline]
+    #[allow(clippy::mut_from_ref)]
+    pub unsafe fn load_mut_unchecked<T: AccountData>(&self) -> Result<&mut T, ProgramError> {
+        self.check_account_data::<T>()?;
+
+        let data = self.borrow_mut_data_unchecked();
+        check_data_layout::<T>(data)?;
+
+        Ok(&mut *(data.as_mut_ptr().add(T::DISCRIMINATOR.len()) as *mut T))
+    }
+
+    /// Checks the owner and the data length of the account for a `T`.
+    #[inline(always)]
+    fn check_account_data<T: AccountData>(&self) -> ProgramResult {
+        let () = LenCheck::<T>::OK;
+
+        if self.owner() != &T::OWNER {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        if self.data_len() != T::DISCRIMINATOR.len() + T::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(())
+    }
+
     /// Realloc the account's data and optionally zero-initialize the new
     /// memory.
     ///
@@ -436,9 +666,14 @@ impl AccountInfo {
         *(self.data_ptr().sub(48) as *mut u64) = 0u64;
         *(self.data_ptr().sub(40) as *mut u64) = 0u64;
         *(self.data_ptr().sub(32) as *mut u64) = 0u64;
-        *(self.data_ptr().sub(16) as *mut u64) = 0u64;
+        *(self.data_ptr().sub(24) as *mut u64) = 0u64;
         // Zero the account lamports.
         (*self.raw).lamports = 0;
+        // Record the original data length (if not already set), since the space
+        // reserved for the account data depends on it.
+        if (*self.raw).original_data_len & SET_LEN_MASK == 0 {
+            (*self.raw).original_data_len = ((*self.raw).data_len as u32) | SET_LEN_MASK;
+        }
         // Zero the account data length.
         (*self.raw).data_len = 0;
     }
@@ -449,6 +684,74 @@ impl AccountInfo {
     }
 }
 
+/// Data of an account owned by a program that can be read in place.
+///
+/// Types implementing this trait can be loaded from an account with
+/// [`AccountInfo::load`] and [`AccountInfo::load_mut`], which check the owner,
+/// length, discriminator and alignment of the account data before returning a
+/// reference to it.
+///
+/// # Safety
+///
+/// Implementors must be plain data types (e.g., `#[repr(C)]` structs of integers and
+/// byte arrays) for which any bit pattern is a valid value, since the account data is
+/// reinterpreted without any further validation.
+///
+/// # Examples
+///
+/// ```
+/// use pinocchio::{account_info::AccountData, pubkey::Pubkey};
+///
+/// #[repr(C)]
+/// pub struct Counter {
+///     pub authority: Pubkey,
+///     pub count: [u8; 8],
+/// }
+///
+/// unsafe impl AccountData for Counter {
+///     const OWNER: Pubkey = [7; 32];
+///     const DISCRIMINATOR: &'static [u8] = &[1];
+/// }
+///
+/// assert_eq!(Counter::LEN, 40);
+/// ```
+pub unsafe trait AccountData: Sized {
+    /// Length of the data, excluding the discriminator.
+    ///
+    /// This must be at least the size of the type.
+    const LEN: usize = size_of::<Self>();
+
+    /// Program that owns the accounts holding this data.
+    const OWNER: Pubkey;
+
+    /// Bytes at the start of the account data identifying the type, which
+    /// are followed by the data.
+    ///
+    /// The default is an empty discriminator, in which case the data starts
+    /// at the beginning of the account data.
+    const DISCRIMINATOR: &'static [u8] = &[];
+}
+
+/// Compile-time check that [`AccountData::LEN`] covers the whole type.
+struct LenCheck<T>(PhantomData<T>);
+
+impl<T: AccountData> LenCheck<T> {
+    /// Fails to evaluate when `LEN` is smaller than the type.
+    const OK: () = assert!(T::LEN >= size_of::<T>(), "`LEN` is smaller than the type");
+}
+
+/// Checks the discriminator and the alignment of the data for a `T`.
+#[inline(always)]
+fn check_data_layout<T: AccountData>(data: &[u8]) -> ProgramResult {
+    let (discriminator, value) = data.split_at(T::DISCRIMINATOR.len());
+
+    if discriminator != T::DISCRIMINATOR || value.as_ptr() as usize % align_of::<T>() != 0 {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    Ok(())
+}
+
 /// Bytes to shift to get to the borrow state of lamports.
 const LAMPORTS_SHIFT: u8 = 4;
 
@@ -719,4 +1022,201 @@ mod tests {
         assert_eq!(lamports, 200);
         assert_eq!(state, 0);
     }
+
+    #[test]
+    fn test_close_unchecked() {
+        #[repr(C)]
+        struct AccountWithData {
+            account: Account,
+            data: [u64; 1],
+        }
+
+        let mut raw = AccountWithData {
+            account: Account {
+                owner: [1; 32],
+                lamports: 100,
+                data_len: 8,
+                ..Default::default()
+            },
+            data: [u64::MAX],
+        };
+        let account = AccountInfo {
+            raw: &mut raw.account,
+        };
+
+        unsafe { account.close_unchecked() };
+
+        assert_eq!(account.owner(), &[0; 32]);
+        assert_eq!(account.lamports(), 0);
+        assert_eq!(account.data_len(), 0);
+        assert_eq!(raw.account.original_data_len, 8 | SET_LEN_MASK);
+    }
+
+    crate::accounts! {
+        struct TestAccounts<'a> {
+            authority: [signer],
+            destination: [writable, owner = [2; 32]],
+            program: [key = [3; 32], executable],
+            other,
+        }
+    }
+
+    fn account(
+        key: u8,
+        owner: u8,
+        is_signer: bool,
+        is_writable: bool,
+        executable: bool,
+    ) -> Account {
+        Account {
+            borrow_state: 0,
+            is_signer: is_signer as u8,
+            is_writable: is_writable as u8,
+            executable: executable as u8,
+            key: [key; 32],
+            owner: [owner; 32],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_accounts_macro() {
+        let mut raw = [
+            account(1, 0, true, false, false),
+            account(2, 2, false, true, false),
+            account(3, 0, false, false, true),
+            account(4, 0, false, false, false),
+        ];
+        let accounts = raw.each_mut().map(|raw| AccountInfo { raw });
+
+        assert_eq!(TestAccounts::LEN, 4);
+
+        let parsed = TestAccounts::try_from(accounts.as_slice()).unwrap();
+        assert_eq!(parsed.authority.key(), &[1; 32]);
+        assert_eq!(parsed.destination.key(), &[2; 32]);
+        assert_eq!(parsed.program.key(), &[3; 32]);
+        assert_eq!(parsed.other.key(), &[4; 32]);
+
+        assert!(matches!(
+            TestAccounts::try_from(&accounts[..3]),
+            Err(ProgramError::NotEnoughAccountKeys)
+        ));
+
+        let checks: [(_, fn(&mut Account), _); 5] = [
+            (
+                0,
+                |a| a.is_signer = 0,
+                ProgramError::MissingRequiredSignature,
+            ),
+            (1, |a| a.is_writable = 0, ProgramError::Immutable),
+            (1, |a| a.owner = [9; 32], ProgramError::InvalidAccountOwner),
+            (2, |a| a.key = [9; 32], ProgramError::InvalidArgument),
+            (2, |a| a.executable = 0, ProgramError::IncorrectProgramId),
+        ];
+
+        for (index, update, error) in checks {
+            let original = unsafe { *accounts[index].raw };
+            update(unsafe { &mut *accounts[index].raw });
+
+            assert_eq!(
+                TestAccounts::try_from(accounts.as_slice()).err(),
+                Some(error)
+            );
+
+            unsafe { *accounts[index].raw = original };
+        }
+    }
+
+    #[repr(C)]
+    struct TestData {
+        amount: u64,
+        other: [u8; 4],
+    }
+
+    unsafe impl AccountData for TestData {
+        const OWNER: Pubkey = [5; 32];
+        const DISCRIMINATOR: &'static [u8] = &[1, 2, 3, 4, 5, 6, 7, 8];
+    }
+
+    #[repr(C)]
+    struct AccountWithData {
+        account: Account,
+        data: [u64; 3],
+    }
+
+    #[test]
+    fn test_load() {
+        let mut raw = AccountWithData {
+            account: Account {
+                owner: [5; 32],
+                data_len: 24,
+                ..Default::default()
+            },
+            data: [u64::from_le_bytes([1, 2, 3, 4, 5, 6, 7, 8]), 42, 0],
+        };
+        let account = AccountInfo {
+            raw: &mut raw.account,
+        };
+
+        assert_eq!(TestData::LEN, 16);
+
+        {
+            let data = account.load::<TestData>().unwrap();
+            assert_eq!(data.amount, 42);
+            assert!(account.load::<TestData>().is_ok());
+            assert_eq!(
+                account.load_mut::<TestData>().err(),
+                Some(ProgramError::AccountBorrowFailed)
+            );
+            // the unchecked variant skips the borrow check
+            assert_eq!(
+                unsafe { account.load_mut_unchecked::<TestData>() }
+                    .unwrap()
+                    .amount,
+                42
+            );
+        }
+
+        account.load_mut::<TestData>().unwrap().amount = 7;
+        assert_eq!(account.load::<TestData>().unwrap().amount, 7);
+        assert_eq!(raw.data[1], 7);
+
+        let checks: [(fn(&mut AccountWithData), _); 3] = [
+            (
+                |a| a.account.owner = [9; 32],
+                ProgramError::InvalidAccountOwner,
+            ),
+            (
+                |a| a.account.data_len = 16,
+                ProgramError::InvalidAccountData,
+            ),
+            (|a| a.data[0] = 0, ProgramError::InvalidAccountData),
+        ];
+
+        for (update, error) in checks {
+            let original = (raw.account, raw.data);
+            update(&mut raw);
+
+            let account = AccountInfo {
+                raw: &mut raw.account,
+            };
+            assert_eq!(account.load::<TestData>().err(), Some(error.clone()));
+            assert_eq!(account.load_mut::<TestData>().err(), Some(error.clone()));
+            unsafe {
+                assert_eq!(
+                    account.load_unchecked::<TestData>().err(),
+                    Some(error.clone())
+                );
+                assert_eq!(account.load_mut_unchecked::<TestData>().err(), Some(error));
+            }
+
+            (raw.account, raw.data) = original;
+        }
+
+        // the data must be aligned for the type
+        let misaligned = check_data_layout::<TestData>(unsafe {
+            core::slice::from_raw_parts((raw.data.as_ptr() as *const u8).add(1), 16)
+        });
+        assert_eq!(misaligned, Err(ProgramError::InvalidAccountData));
+    }
 }
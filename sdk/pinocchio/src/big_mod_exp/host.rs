@@ -0,0 +1,216 @@
+//! Host implementation of the big integer modular exponentiation.
+//!
+//! This is a straightforward (non-optimized and not constant-time) implementation
+//! used when the program is not running on the SBF target. Values are stored in
+//! fixed-size buffers of little-endian `u32` limbs, so no allocation is required.
+
+use super::MAX_BIG_MOD_EXP_LEN;
+
+/// Maximum number of limbs of an input value.
+const MAX_LIMBS: usize = MAX_BIG_MOD_EXP_LEN / 4;
+
+/// A big integer represented as little-endian `u32` limbs.
+type Limbs = [u32; MAX_LIMBS];
+
+/// Compute `base ^ exponent mod modulus`, writing the big-endian result into `result`.
+///
+/// The caller must ensure that the inputs are at most [`MAX_BIG_MOD_EXP_LEN`] bytes
+/// long and that `result` has the same length as `modulus`.
+pub fn big_mod_exp(base: &[u8], exponent: &[u8], modulus: &[u8], result: &mut [u8]) {
+    result.fill(0);
+
+    let modulus_limbs = from_be_bytes(modulus);
+    let n = significant_limbs(&modulus_limbs);
+
+    // a modulus of `0` or `1` always results in `0`
+    if n == 0 || (n == 1 && modulus_limbs[0] == 1) {
+        return;
+    }
+
+    let modulus = &modulus_limbs[..n];
+
+    let mut value = [0u32; MAX_LIMBS];
+    let base = from_be_bytes(base);
+    reduce(
+        &base[..significant_limbs(&base).max(1)],
+        modulus,
+        &mut value[..n],
+    );
+
+    let mut accumulator = [0u32; MAX_LIMBS];
+    accumulator[0] = 1;
+
+    // left-to-right square-and-multiply over the exponent bits
+    for byte in exponent {
+        for bit in (0..8).rev() {
+            let squared = accumulator;
+            mul_mod(&squared[..n], &squared[..n], modulus, &mut accumulator[..n]);
+
+            if (byte >> bit) & 1 == 1 {
+                let current = accumulator;
+                mul_mod(&current[..n], &value[..n], modulus, &mut accumulator[..n]);
+            }
+        }
+    }
+
+    to_be_bytes(&accumulator[..n], result);
+}
+
+/// Convert big-endian bytes into limbs.
+fn from_be_bytes(bytes: &[u8]) -> Limbs {
+    let mut limbs = [0u32; MAX_LIMBS];
+
+    for (i, byte) in bytes.iter().rev().enumerate() {
+        limbs[i / 4] |= (*byte as u32) << ((i % 4) * 8);
+    }
+
+    limbs
+}
+
+/// Write the limbs as left-padded big-endian bytes.
+fn to_be_bytes(limbs: &[u32], bytes: &mut [u8]) {
+    for (i, byte) in bytes.iter_mut().rev().enumerate() {
+        if let Some(limb) = limbs.get(i / 4) {
+            *byte = (limb >> ((i % 4) * 8)) as u8;
+        }
+    }
+}
+
+/// Return the number of limbs ignoring the most significant zero limbs.
+fn significant_limbs(limbs: &[u32]) -> usize {
+    limbs
+        .iter()
+        .rposition(|limb| *limb != 0)
+        .map_or(0, |position| position + 1)
+}
+
+/// Compute `(left * right) mod modulus`.
+///
+/// Both `left` and `right` must be reduced and have the same number of limbs as
+/// the `modulus`.
+fn mul_mod(left: &[u32], right: &[u32], modulus: &[u32], result: &mut [u32]) {
+    let mut product = [0u32; 2 * MAX_LIMBS];
+    let n = modulus.len();
+
+    for (i, l) in left.iter().enumerate() {
+        let mut carry = 0u64;
+
+        for (j, r) in right.iter().enumerate() {
+            let t = (*l as u64) * (*r as u64) + product[i + j] as u64 + carry;
+            product[i + j] = t as u32;
+            carry = t >> 32;
+        }
+
+        product[i + n] = carry as u32;
+    }
+
+    reduce(&product[..2 * n], modulus, result);
+}
+
+/// Compute `value mod modulus` using the long division algorithm (Knuth's
+/// algorithm D).
+///
+/// The most significant limb of the `modulus` must be non-zero and `result` must
+/// have the same number of limbs as the `modulus`.
+fn reduce(value: &[u32], modulus: &[u32], result: &mut [u32]) {
+    let n = modulus.len();
+    result.fill(0);
+
+    if value.len() < n {
+        result[..value.len()].copy_from_slice(value);
+        return;
+    }
+
+    if n == 1 {
+        let divisor = modulus[0] as u64;
+        let remainder = value.iter().rev().fold(0u64, |remainder, limb| {
+            ((remainder << 32) | *limb as u64) % divisor
+        });
+        result[0] = remainder as u32;
+        return;
+    }
+
+    let m = value.len() - n;
+    let shift = modulus[n - 1].leading_zeros();
+
+    // normalize the divisor so that its most significant bit is set
+    let mut divisor = [0u32; MAX_LIMBS];
+    shift_left(modulus, shift, &mut divisor[..n]);
+
+    let mut dividend = [0u32; 2 * MAX_LIMBS + 1];
+    dividend[value.len()] = shift_left(value, shift, &mut dividend[..value.len()]);
+
+    let top = divisor[n - 1] as u64;
+    let next = divisor[n - 2] as u64;
+
+    for j in (0..=m).rev() {
+        // estimate the quotient digit
+        let numerator = ((dividend[j + n] as u64) << 32) | dividend[j + n - 1] as u64;
+        let mut quotient = numerator / top;
+        let mut remainder = numerator % top;
+
+        while quotient > u32::MAX as u64
+            || quotient * next > ((remainder << 32) | dividend[j + n - 2] as u64)
+        {
+            quotient -= 1;
+            remainder += top;
+
+            if remainder > u32::MAX as u64 {
+                break;
+            }
+        }
+
+        // multiply and subtract
+        let mut borrow = 0i64;
+
+        for i in 0..n {
+            let product = quotient * divisor[i] as u64;
+            let t = dividend[i + j] as i64 - borrow - (product & u32::MAX as u64) as i64;
+            dividend[i + j] = t as u32;
+            borrow = (product >> 32) as i64 - (t >> 32);
+        }
+
+        let t = dividend[j + n] as i64 - borrow;
+        dividend[j + n] = t as u32;
+
+        // the estimate was one too large, add the divisor back
+        if t < 0 {
+            let mut carry = 0u64;
+
+            for i in 0..n {
+                let t = dividend[i + j] as u64 + divisor[i] as u64 + carry;
+                dividend[i + j] = t as u32;
+                carry = t >> 32;
+            }
+
+            dividend[j + n] = dividend[j + n].wrapping_add(carry as u32);
+        }
+    }
+
+    // unnormalize the remainder
+    for i in 0..n {
+        result[i] = if shift == 0 {
+            dividend[i]
+        } else {
+            (dividend[i] >> shift) | (dividend[i + 1] << (32 - shift))
+        };
+    }
+}
+
+/// Shift the `value` left by `shift` bits (less than 32), returning the bits
+/// shifted out of the most significant limb.
+fn shift_left(value: &[u32], shift: u32, result: &mut [u32]) -> u32 {
+    if shift == 0 {
+        result.copy_from_slice(value);
+        return 0;
+    }
+
+    let mut carry = 0u32;
+
+    for (r, v) in result.iter_mut().zip(value) {
+        *r = (v << shift) | carry;
+        carry = v >> (32 - shift);
+    }
+
+    carry
+}
@@ -0,0 +1,166 @@
+//! Modular exponentiation of big integers.
+//!
+//! The exponentiation is performed by the runtime through the `sol_big_mod_exp`
+//! syscall, which is useful to verify RSA signatures within a program. Off-chain,
+//! the same computation is performed by a host implementation so that programs
+//! can be unit tested.
+
+#[cfg(not(target_os = "solana"))]
+mod host;
+
+use crate::program_error::ProgramError;
+
+/// Maximum length (in bytes) of the base, exponent and modulus.
+pub const MAX_BIG_MOD_EXP_LEN: usize = 512;
+
+/// Parameters expected by the `sol_big_mod_exp` syscall.
+///
+/// All values are represented as big-endian byte slices.
+#[repr(C)]
+struct BigModExpParams {
+    /// Pointer to the base bytes.
+    base: *const u8,
+
+    /// Length of the base.
+    base_len: u64,
+
+    /// Pointer to the exponent bytes.
+    exponent: *const u8,
+
+    /// Length of the exponent.
+    exponent_len: u64,
+
+    /// Pointer to the modulus bytes.
+    modulus: *const u8,
+
+    /// Length of the modulus.
+    modulus_len: u64,
+}
+
+/// Compute `base ^ exponent mod modulus`, writing the result into `result`.
+///
+/// All values are big-endian byte slices of at most [`MAX_BIG_MOD_EXP_LEN`] bytes.
+/// The `result` must have the same length as the `modulus`; the value is written
+/// left-padded with zeros. When the `modulus` is either `0` or `1`, the result is
+/// always `0`.
+///
+/// # Errors
+///
+/// Returns [`ProgramError::InvalidArgument`] if any of the inputs is longer than
+/// [`MAX_BIG_MOD_EXP_LEN`] or the length of the `result` does not match the length
+/// of the `modulus`.
+#[inline]
+pub fn big_mod_exp(
+    base: &[u8],
+    exponent: &[u8],
+    modulus: &[u8],
+    result: &mut [u8],
+) -> Result<(), ProgramError> {
+    if base.len() > MAX_BIG_MOD_EXP_LEN
+        || exponent.len() > MAX_BIG_MOD_EXP_LEN
+        || modulus.len() > MAX_BIG_MOD_EXP_LEN
+        || result.len() != modulus.len()
+    {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let params = BigModExpParams {
+        base: base.as_ptr(),
+        base_len: base.len() as u64,
+        exponent: exponent.as_ptr(),
+        exponent_len: exponent.len() as u64,
+        modulus: modulus.as_ptr(),
+        modulus_len: modulus.len() as u64,
+    };
+
+    #[cfg(target_os = "solana")]
+    {
+        let status = unsafe {
+            crate::syscalls::sol_big_mod_exp(&params as *const _ as *const u8, result.as_mut_ptr())
+        };
+
+        match status {
+            crate::SUCCESS => Ok(()),
+            _ => Err(status.into()),
+        }
+    }
+
+    #[cfg(not(target_os = "solana"))]
+    {
+        core::hint::black_box(&params);
+        host::big_mod_exp(base, exponent, modulus, result);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Decode a hex string into a byte array.
+    fn hex<const N: usize>(value: &str) -> [u8; N] {
+        let mut bytes = [0u8; N];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&value[i * 2..i * 2 + 2], 16).unwrap();
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_small_values() {
+        let mut result = [0u8; 2];
+
+        big_mod_exp(&[3], &[5], &[0x01, 0x00], &mut result).unwrap();
+        assert_eq!(result, [0, 243]);
+
+        big_mod_exp(&[0x12, 0x34, 0x56], &[0x07], &[0x01, 0x01], &mut result).unwrap();
+        assert_eq!(result, [0, 42]);
+
+        // a zero exponent
+        big_mod_exp(&[9], &[], &[0x00, 0x10], &mut result).unwrap();
+        assert_eq!(result, [0, 1]);
+
+        // a modulus of one
+        big_mod_exp(&[9], &[3], &[0x00, 0x01], &mut result).unwrap();
+        assert_eq!(result, [0, 0]);
+
+        // a modulus of zero
+        big_mod_exp(&[9], &[3], &[0x00, 0x00], &mut result).unwrap();
+        assert_eq!(result, [0, 0]);
+
+        let mut result = [];
+        big_mod_exp(&[9], &[3], &[], &mut result).unwrap();
+    }
+
+    #[test]
+    fn test_large_values() {
+        let base = hex::<64>("c6a5387777330bdbd7210dff076ce2ef87b0b125ec1d7da0a6eb8c9ebd69fe29d76d4330f1446beab0c11fdecb91ce375bc8fbbcbde5c0994164d8399f767c45");
+        let exponent = hex::<64>("bfd4235992edcf451a1afe878b33e968617959ce3f1f65a8de5271007814e8a25f2dd97f1cfb10f62827688de6a16a3b0d464138a62332553fc1ea36f17fd374");
+        // an even modulus
+        let modulus = hex::<64>("9e30691c238642ea126a1e48cc11d357c30d8b7628dbd25e63b229f1c4069545de11cc9dea959c212e9c82b1478c281d687c966c377b9aa2bb2edb20035b7398");
+
+        let mut result = [0u8; 64];
+        big_mod_exp(&base, &exponent, &modulus, &mut result).unwrap();
+
+        assert_eq!(
+            result,
+            hex::<64>("16fd78b87341ab86352449338bc7480659c9766cfb74f02061986352fe33eb6b73e2a7f5a83cbb54397a81b55c32539c03d3ea785b92f8363b17bb5f3e0bc121")
+        );
+    }
+
+    #[test]
+    fn test_invalid_lengths() {
+        let mut result = [0u8; 2];
+
+        assert_eq!(
+            big_mod_exp(&[3], &[5], &[0x01, 0x00, 0x00], &mut result),
+            Err(ProgramError::InvalidArgument)
+        );
+
+        let base = [1u8; MAX_BIG_MOD_EXP_LEN + 1];
+        assert_eq!(
+            big_mod_exp(&base, &[5], &[0x01, 0x00], &mut result),
+            Err(ProgramError::InvalidArgument)
+        );
+    }
+}
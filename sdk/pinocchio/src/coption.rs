@@ -0,0 +1,232 @@
+//! A C representation of an optional value.
+
+use core::{
+    marker::PhantomData,
+    mem::align_of,
+    ptr::{addr_of, addr_of_mut, read_unaligned, write_unaligned},
+};
+
+/// Tag indicating that the value is present.
+const SOME: [u8; 4] = [1, 0, 0, 0];
+
+/// Tag indicating that the value is not present.
+const NONE: [u8; 4] = [0; 4];
+
+/// An optional value with a C-compatible layout.
+///
+/// The value is preceded by a 4-byte little-endian tag, which is `1` when the value
+/// is present and `0` otherwise. This is the layout used by the SPL programs (e.g.,
+/// the `mint_authority` of a token mint) to store optional values in account data.
+///
+/// The type has no padding and an alignment of 1, so it can be read in place from any
+/// offset of the account data. Since the value might not be aligned, it is read by copy
+/// with [`COption::as_option`]; references to the value are only available for types
+/// with an alignment of 1 (e.g., [`Pubkey`]).
+///
+/// When the value is not present, it is reset to its default value (e.g., zeroed for
+/// a [`Pubkey`] or a `u64`).
+///
+/// [`Pubkey`]: crate::pubkey::Pubkey
+#[repr(C, packed)]
+#[derive(Clone, Copy)]
+pub struct COption<T> {
+    /// Indicates whether the value is present or not.
+    tag: [u8; 4],
+
+    /// The value, which is only meaningful when the tag is set.
+    value: T,
+}
+
+impl<T> COption<T> {
+    /// Create a `COption` with a value.
+    #[inline(always)]
+    pub const fn some(value: T) -> Self {
+        Self { tag: SOME, value }
+    }
+
+    /// Create a `COption` without a value.
+    #[inline(always)]
+    pub fn none() -> Self
+    where
+        T: Default,
+    {
+        Self {
+            tag: NONE,
+            value: T::default(),
+        }
+    }
+
+    /// Return `true` if the value is present.
+    #[inline(always)]
+    pub fn is_some(&self) -> bool {
+        self.tag == SOME
+    }
+
+    /// Return `true` if the value is not present.
+    #[inline(always)]
+    pub fn is_none(&self) -> bool {
+        !self.is_some()
+    }
+
+    /// Return `true` if the tag is either `0` or `1`.
+    ///
+    /// Other tag values are treated as `None`, but they are not a valid encoding.
+    #[inline(always)]
+    pub fn is_valid(&self) -> bool {
+        self.tag == SOME || self.tag == NONE
+    }
+
+    /// Return a copy of the value, if present.
+    #[inline(always)]
+    pub fn as_option(&self) -> Option<T>
+    where
+        T: Copy,
+    {
+        if self.is_some() {
+            Some(self.value_unchecked())
+        } else {
+            None
+        }
+    }
+
+    /// Return a copy of the value, without checking whether it is present.
+    ///
+    /// This method should be used when the caller knows that the value is present
+    /// since it skips the tag check.
+    #[inline(always)]
+    pub fn value_unchecked(&self) -> T
+    where
+        T: Copy,
+    {
+        unsafe { read_unaligned(addr_of!(self.value)) }
+    }
+
+    /// Return a reference to the value, if present.
+    ///
+    /// This method is only available for types with an alignment of 1.
+    #[inline(always)]
+    pub fn as_ref(&self) -> Option<&T> {
+        if self.is_some() {
+            Some(self.as_ref_unchecked())
+        } else {
+            None
+        }
+    }
+
+    /// Return a reference to the value, without checking whether it is present.
+    ///
+    /// This method should be used when the caller knows that the value is present
+    /// since it skips the tag check. It is only available for types with an alignment
+    /// of 1.
+    #[inline(always)]
+    pub fn as_ref_unchecked(&self) -> &T {
+        let () = AlignCheck::<T>::OK;
+        // SAFETY: the value has an alignment of 1, so it is always aligned.
+        unsafe { &*addr_of!(self.value) }
+    }
+
+    /// Set the value, resetting it to its default when `None`.
+    #[inline(always)]
+    pub fn set(&mut self, value: Option<T>)
+    where
+        T: Default,
+    {
+        let (tag, value) = match value {
+            Some(value) => (SOME, value),
+            None => (NONE, T::default()),
+        };
+
+        self.tag = tag;
+        unsafe { write_unaligned(addr_of_mut!(self.value), value) };
+    }
+}
+
+/// Compile-time check that a value can be referenced in place.
+struct AlignCheck<T>(PhantomData<T>);
+
+impl<T> AlignCheck<T> {
+    /// Fails to evaluate when `T` has an alignment other than 1.
+    const OK: () = assert!(align_of::<T>() == 1, "the value is not aligned");
+}
+
+impl<T: Default> Default for COption<T> {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+impl<T: Default> From<Option<T>> for COption<T> {
+    fn from(value: Option<T>) -> Self {
+        match value {
+            Some(value) => Self::some(value),
+            None => Self::none(),
+        }
+    }
+}
+
+impl<T: Copy> From<COption<T>> for Option<T> {
+    fn from(value: COption<T>) -> Self {
+        value.as_option()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::mem::size_of;
+
+    use super::*;
+    use crate::pubkey::Pubkey;
+
+    #[test]
+    fn test_layout() {
+        assert_eq!(size_of::<COption<Pubkey>>(), 36);
+        assert_eq!(align_of::<COption<Pubkey>>(), 1);
+        assert_eq!(size_of::<COption<u64>>(), 12);
+        assert_eq!(align_of::<COption<u64>>(), 1);
+    }
+
+    #[test]
+    fn test_coption() {
+        let mut bytes = [0u8; 13];
+
+        // an unaligned `COption<u64>`
+        let option = unsafe { &mut *(bytes.as_mut_ptr().add(1) as *mut COption<u64>) };
+        assert!(option.is_none());
+        assert_eq!(option.as_option(), None);
+
+        option.set(Some(u64::MAX - 1));
+        assert!(option.is_some());
+        assert_eq!(option.as_option(), Some(u64::MAX - 1));
+        assert_eq!(bytes[1..5], [1, 0, 0, 0]);
+        assert_eq!(bytes[5..], (u64::MAX - 1).to_le_bytes());
+
+        let option = unsafe { &mut *(bytes.as_mut_ptr().add(1) as *mut COption<u64>) };
+        option.set(None);
+        assert_eq!(bytes, [0; 13]);
+
+        // any tag other than `1` means that the value is not present
+        bytes[1] = 2;
+        bytes[5] = 1;
+        let option = unsafe { &*(bytes.as_ptr().add(1) as *const COption<u64>) };
+        assert_eq!(option.as_option(), None);
+        assert_eq!(option.value_unchecked(), 1);
+        assert!(!option.is_valid());
+    }
+
+    #[test]
+    fn test_coption_pubkey() {
+        let key: Pubkey = [7; 32];
+
+        let mut option = COption::some(key);
+        assert_eq!(option.as_ref(), Some(&key));
+        assert_eq!(option.as_ref_unchecked(), &key);
+
+        option.set(None);
+        assert_eq!(option.as_ref(), None);
+        assert_eq!(option.as_ref_unchecked(), &[0; 32]);
+
+        assert!(COption::<Pubkey>::default().is_none());
+        assert_eq!(Option::from(COption::from(Some(key))), Some(key));
+        assert_eq!(Option::<Pubkey>::from(COption::from(None)), None);
+    }
+}
@@ -0,0 +1,371 @@
+//! Operations on the BN254 (`alt_bn128`) curve.
+//!
+//! Points use the big-endian encoding of [EIP-196] and [EIP-197]: a G1 point is
+//! encoded as its `x` and `y` coordinates (32 bytes each), and a G2 point as the
+//! `x` and `y` coordinates over the quadratic extension field, where each element
+//! `c0 + c1 * i` is encoded as `c1 || c0`. The point at infinity is represented by
+//! all zero bytes.
+//!
+//! Off-chain, all operations fail with [`AltBn128Error::UnexpectedError`] or
+//! [`AltBn128CompressionError::UnexpectedError`].
+//!
+//! [EIP-196]: https://eips.ethereum.org/EIPS/eip-196
+//! [EIP-197]: https://eips.ethereum.org/EIPS/eip-197
+
+use crate::program_error::ProgramError;
+
+/// Identifier of the G1 addition operation.
+pub const ALT_BN128_ADD: u64 = 0;
+
+/// Identifier of the G1 scalar multiplication operation.
+pub const ALT_BN128_MUL: u64 = 2;
+
+/// Identifier of the pairing check operation.
+pub const ALT_BN128_PAIRING: u64 = 3;
+
+/// Identifier of the G1 compression operation.
+pub const ALT_BN128_G1_COMPRESS: u64 = 0;
+
+/// Identifier of the G1 decompression operation.
+pub const ALT_BN128_G1_DECOMPRESS: u64 = 1;
+
+/// Identifier of the G2 compression operation.
+pub const ALT_BN128_G2_COMPRESS: u64 = 2;
+
+/// Identifier of the G2 decompression operation.
+pub const ALT_BN128_G2_DECOMPRESS: u64 = 3;
+
+/// Length of an uncompressed G1 point.
+pub const G1_POINT_LEN: usize = 64;
+
+/// Length of an uncompressed G2 point.
+pub const G2_POINT_LEN: usize = 128;
+
+/// Length of a compressed G1 point.
+pub const G1_COMPRESSED_POINT_LEN: usize = 32;
+
+/// Length of a compressed G2 point.
+pub const G2_COMPRESSED_POINT_LEN: usize = 64;
+
+/// Length of a scalar.
+pub const SCALAR_LEN: usize = 32;
+
+/// Errors that can be returned by the group and pairing operations.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AltBn128Error {
+    /// The input data is invalid.
+    InvalidInputData,
+
+    /// A point is not a valid group element.
+    GroupError,
+
+    /// The input is out of bounds.
+    SliceOutOfBounds,
+
+    /// Failed to convert the input.
+    TryIntoVecError,
+
+    /// Failed to convert the result into a G1 point.
+    ProjectiveToG1Failed,
+
+    /// Unexpected error.
+    UnexpectedError,
+}
+
+impl From<u64> for AltBn128Error {
+    fn from(error: u64) -> Self {
+        match error {
+            1 => AltBn128Error::InvalidInputData,
+            2 => AltBn128Error::GroupError,
+            3 => AltBn128Error::SliceOutOfBounds,
+            4 => AltBn128Error::TryIntoVecError,
+            5 => AltBn128Error::ProjectiveToG1Failed,
+            _ => AltBn128Error::UnexpectedError,
+        }
+    }
+}
+
+impl From<AltBn128Error> for ProgramError {
+    fn from(_: AltBn128Error) -> Self {
+        ProgramError::InvalidArgument
+    }
+}
+
+/// Errors that can be returned by the compression operations.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AltBn128CompressionError {
+    /// Failed to decompress a G1 point.
+    G1DecompressionFailed,
+
+    /// Failed to decompress a G2 point.
+    G2DecompressionFailed,
+
+    /// Failed to compress a G1 point.
+    G1CompressionFailed,
+
+    /// Failed to compress a G2 point.
+    G2CompressionFailed,
+
+    /// The input size is invalid.
+    InvalidInputSize,
+
+    /// Unexpected error.
+    UnexpectedError,
+}
+
+impl From<u64> for AltBn128CompressionError {
+    fn from(error: u64) -> Self {
+        match error {
+            1 => AltBn128CompressionError::G1DecompressionFailed,
+            2 => AltBn128CompressionError::G2DecompressionFailed,
+            3 => AltBn128CompressionError::G1CompressionFailed,
+            4 => AltBn128CompressionError::G2CompressionFailed,
+            5 => AltBn128CompressionError::InvalidInputSize,
+            _ => AltBn128CompressionError::UnexpectedError,
+        }
+    }
+}
+
+impl From<AltBn128CompressionError> for ProgramError {
+    fn from(_: AltBn128CompressionError) -> Self {
+        ProgramError::InvalidArgument
+    }
+}
+
+/// A point on the G1 group.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PodG1Point(pub [u8; G1_POINT_LEN]);
+
+/// A point on the G2 group.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PodG2Point(pub [u8; G2_POINT_LEN]);
+
+/// A compressed point on the G1 group.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PodG1CompressedPoint(pub [u8; G1_COMPRESSED_POINT_LEN]);
+
+/// A compressed point on the G2 group.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PodG2CompressedPoint(pub [u8; G2_COMPRESSED_POINT_LEN]);
+
+/// A pair of G1 and G2 points used as input of a pairing check.
+///
+/// This type has the same layout expected by the pairing syscall, so a slice of
+/// `PairingElement`s can be passed to it directly.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PairingElement {
+    /// The G1 point.
+    pub g1: PodG1Point,
+
+    /// The G2 point.
+    pub g2: PodG2Point,
+}
+
+impl Default for PodG1Point {
+    fn default() -> Self {
+        Self([0; G1_POINT_LEN])
+    }
+}
+
+impl Default for PodG2Point {
+    fn default() -> Self {
+        Self([0; G2_POINT_LEN])
+    }
+}
+
+impl PodG1Point {
+    /// Return the sum of two G1 points.
+    #[inline]
+    pub fn add(&self, other: &Self) -> Result<Self, AltBn128Error> {
+        let mut input = [0u8; 2 * G1_POINT_LEN];
+        input[..G1_POINT_LEN].copy_from_slice(&self.0);
+        input[G1_POINT_LEN..].copy_from_slice(&other.0);
+
+        let mut result = [0u8; G1_POINT_LEN];
+        group_op(ALT_BN128_ADD, &input, &mut result)?;
+
+        Ok(Self(result))
+    }
+
+    /// Return the product of the G1 point by a big-endian scalar.
+    #[inline]
+    pub fn multiply(&self, scalar: &[u8; SCALAR_LEN]) -> Result<Self, AltBn128Error> {
+        let mut input = [0u8; G1_POINT_LEN + SCALAR_LEN];
+        input[..G1_POINT_LEN].copy_from_slice(&self.0);
+        input[G1_POINT_LEN..].copy_from_slice(scalar);
+
+        let mut result = [0u8; G1_POINT_LEN];
+        group_op(ALT_BN128_MUL, &input, &mut result)?;
+
+        Ok(Self(result))
+    }
+
+    /// Return the compressed representation of the G1 point.
+    #[inline]
+    pub fn compress(&self) -> Result<PodG1CompressedPoint, AltBn128CompressionError> {
+        let mut result = [0u8; G1_COMPRESSED_POINT_LEN];
+        compression_op(ALT_BN128_G1_COMPRESS, &self.0, &mut result)?;
+        Ok(PodG1CompressedPoint(result))
+    }
+}
+
+impl PodG1CompressedPoint {
+    /// Return the uncompressed representation of the G1 point.
+    #[inline]
+    pub fn decompress(&self) -> Result<PodG1Point, AltBn128CompressionError> {
+        let mut result = [0u8; G1_POINT_LEN];
+        compression_op(ALT_BN128_G1_DECOMPRESS, &self.0, &mut result)?;
+        Ok(PodG1Point(result))
+    }
+}
+
+impl PodG2Point {
+    /// Return the compressed representation of the G2 point.
+    #[inline]
+    pub fn compress(&self) -> Result<PodG2CompressedPoint, AltBn128CompressionError> {
+        let mut result = [0u8; G2_COMPRESSED_POINT_LEN];
+        compression_op(ALT_BN128_G2_COMPRESS, &self.0, &mut result)?;
+        Ok(PodG2CompressedPoint(result))
+    }
+}
+
+impl PodG2CompressedPoint {
+    /// Return the uncompressed representation of the G2 point.
+    #[inline]
+    pub fn decompress(&self) -> Result<PodG2Point, AltBn128CompressionError> {
+        let mut result = [0u8; G2_POINT_LEN];
+        compression_op(ALT_BN128_G2_DECOMPRESS, &self.0, &mut result)?;
+        Ok(PodG2Point(result))
+    }
+}
+
+/// Perform a pairing check on the given elements.
+///
+/// Returns `true` if the product of the pairings of all elements is equal to the
+/// identity of the target group. An empty list of elements is considered valid.
+#[inline]
+pub fn pairing(elements: &[PairingElement]) -> Result<bool, AltBn128Error> {
+    let input = unsafe {
+        core::slice::from_raw_parts(
+            elements.as_ptr() as *const u8,
+            core::mem::size_of_val(elements),
+        )
+    };
+
+    let mut result = [0u8; 32];
+    group_op(ALT_BN128_PAIRING, input, &mut result)?;
+
+    // the result is a big-endian `1` when the pairing check succeeds
+    Ok(result[31] == 1 && result[..31].iter().all(|byte| *byte == 0))
+}
+
+/// Perform a group operation using the `sol_alt_bn128_group_op` syscall.
+#[inline(always)]
+fn group_op(op: u64, input: &[u8], result: &mut [u8]) -> Result<(), AltBn128Error> {
+    #[cfg(target_os = "solana")]
+    {
+        let status = unsafe {
+            crate::syscalls::sol_alt_bn128_group_op(
+                op,
+                input.as_ptr(),
+                input.len() as u64,
+                result.as_mut_ptr(),
+            )
+        };
+
+        match status {
+            crate::SUCCESS => Ok(()),
+            _ => Err(status.into()),
+        }
+    }
+
+    #[cfg(not(target_os = "solana"))]
+    {
+        core::hint::black_box((op, input, result));
+        Err(AltBn128Error::UnexpectedError)
+    }
+}
+
+/// Perform a compression operation using the `sol_alt_bn128_compression` syscall.
+#[inline(always)]
+fn compression_op(
+    op: u64,
+    input: &[u8],
+    result: &mut [u8],
+) -> Result<(), AltBn128CompressionError> {
+    #[cfg(target_os = "solana")]
+    {
+        let status = unsafe {
+            crate::syscalls::sol_alt_bn128_compression(
+                op,
+                input.as_ptr(),
+                input.len() as u64,
+                result.as_mut_ptr(),
+            )
+        };
+
+        match status {
+            crate::SUCCESS => Ok(()),
+            _ => Err(status.into()),
+        }
+    }
+
+    #[cfg(not(target_os = "solana"))]
+    {
+        core::hint::black_box((op, input, result));
+        Err(AltBn128CompressionError::UnexpectedError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pairing_element_layout() {
+        assert_eq!(
+            core::mem::size_of::<PairingElement>(),
+            G1_POINT_LEN + G2_POINT_LEN
+        );
+        assert_eq!(core::mem::align_of::<PairingElement>(), 1);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(AltBn128Error::from(1), AltBn128Error::InvalidInputData);
+        assert_eq!(AltBn128Error::from(6), AltBn128Error::UnexpectedError);
+        assert_eq!(
+            AltBn128CompressionError::from(5),
+            AltBn128CompressionError::InvalidInputSize
+        );
+        assert_eq!(
+            AltBn128CompressionError::from(0),
+            AltBn128CompressionError::UnexpectedError
+        );
+    }
+
+    #[test]
+    fn test_off_chain() {
+        let point = PodG1Point::default();
+
+        assert_eq!(point.add(&point), Err(AltBn128Error::UnexpectedError));
+        assert_eq!(
+            point.multiply(&[0; SCALAR_LEN]),
+            Err(AltBn128Error::UnexpectedError)
+        );
+        assert_eq!(pairing(&[]), Err(AltBn128Error::UnexpectedError));
+        assert_eq!(
+            point.compress(),
+            Err(AltBn128CompressionError::UnexpectedError)
+        );
+        assert_eq!(
+            PodG2CompressedPoint([0; G2_COMPRESSED_POINT_LEN]).decompress(),
+            Err(AltBn128CompressionError::UnexpectedError)
+        );
+    }
+}
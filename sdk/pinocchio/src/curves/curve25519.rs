@@ -0,0 +1,240 @@
+//! Group operations on Curve25519.
+//!
+//! Points are represented in their compressed 32-byte encoding and scalars in
+//! their canonical 32-byte little-endian encoding. Operations on invalid points or
+//! non-canonical scalars fail with [`Curve25519Error::OperationFailed`].
+//!
+//! Off-chain, points are never valid and all operations fail with
+//! [`Curve25519Error::OperationFailed`].
+
+use crate::program_error::ProgramError;
+
+/// Identifier of the Edwards representation of Curve25519.
+pub const CURVE25519_EDWARDS: u64 = 0;
+
+/// Identifier of the Ristretto representation of Curve25519.
+pub const CURVE25519_RISTRETTO: u64 = 1;
+
+/// Identifier of the group addition operation.
+pub const ADD: u64 = 0;
+
+/// Identifier of the group subtraction operation.
+pub const SUB: u64 = 1;
+
+/// Identifier of the scalar multiplication operation.
+pub const MUL: u64 = 2;
+
+/// Maximum number of points supported by a multiscalar multiplication.
+pub const MAX_MULTISCALAR_POINTS: usize = 512;
+
+/// Errors that can be returned by the Curve25519 operations.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Curve25519Error {
+    /// The number of scalars and points do not match or exceed
+    /// [`MAX_MULTISCALAR_POINTS`].
+    InvalidLength,
+
+    /// The operation failed due to an invalid point or scalar.
+    OperationFailed,
+}
+
+impl From<Curve25519Error> for ProgramError {
+    fn from(_: Curve25519Error) -> Self {
+        ProgramError::InvalidArgument
+    }
+}
+
+/// A scalar of the Curve25519 group in its canonical little-endian encoding.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct PodScalar(pub [u8; 32]);
+
+/// A point in the Edwards representation of Curve25519, in its compressed encoding.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct PodEdwardsPoint(pub [u8; 32]);
+
+/// A point in the Ristretto representation of Curve25519, in its compressed encoding.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct PodRistrettoPoint(pub [u8; 32]);
+
+/// Implements the group operations for a point type.
+macro_rules! impl_group_operations {
+    ($point:ident, $curve_id:expr) => {
+        impl $point {
+            /// Indicates whether the point is a valid point on the curve.
+            #[inline]
+            pub fn validate(&self) -> bool {
+                #[cfg(target_os = "solana")]
+                {
+                    let mut result = 0u8;
+                    let status = unsafe {
+                        crate::syscalls::sol_curve_validate_point(
+                            $curve_id,
+                            self.0.as_ptr(),
+                            &mut result,
+                        )
+                    };
+                    status == crate::SUCCESS
+                }
+
+                #[cfg(not(target_os = "solana"))]
+                {
+                    core::hint::black_box(self);
+                    false
+                }
+            }
+
+            /// Return the sum of two points.
+            #[inline]
+            pub fn add(&self, other: &Self) -> Result<Self, Curve25519Error> {
+                group_op($curve_id, ADD, &self.0, &other.0).map(Self)
+            }
+
+            /// Return the difference of two points.
+            #[inline]
+            pub fn subtract(&self, other: &Self) -> Result<Self, Curve25519Error> {
+                group_op($curve_id, SUB, &self.0, &other.0).map(Self)
+            }
+
+            /// Return the product of the point by a scalar.
+            #[inline]
+            pub fn multiply(&self, scalar: &PodScalar) -> Result<Self, Curve25519Error> {
+                group_op($curve_id, MUL, &scalar.0, &self.0).map(Self)
+            }
+
+            /// Return the sum of the products of each point by its corresponding scalar.
+            ///
+            /// The number of `scalars` and `points` must match and cannot exceed
+            /// [`MAX_MULTISCALAR_POINTS`].
+            #[inline]
+            pub fn multiscalar_multiply(
+                scalars: &[PodScalar],
+                points: &[Self],
+            ) -> Result<Self, Curve25519Error> {
+                if scalars.len() != points.len() || points.len() > MAX_MULTISCALAR_POINTS {
+                    return Err(Curve25519Error::InvalidLength);
+                }
+
+                #[cfg(target_os = "solana")]
+                {
+                    let mut result = [0u8; 32];
+                    let status = unsafe {
+                        crate::syscalls::sol_curve_multiscalar_mul(
+                            $curve_id,
+                            scalars.as_ptr() as *const u8,
+                            points.as_ptr() as *const u8,
+                            points.len() as u64,
+                            result.as_mut_ptr(),
+                        )
+                    };
+
+                    match status {
+                        crate::SUCCESS => Ok(Self(result)),
+                        _ => Err(Curve25519Error::OperationFailed),
+                    }
+                }
+
+                #[cfg(not(target_os = "solana"))]
+                {
+                    core::hint::black_box((scalars, points));
+                    Err(Curve25519Error::OperationFailed)
+                }
+            }
+        }
+    };
+}
+
+impl_group_operations!(PodEdwardsPoint, CURVE25519_EDWARDS);
+impl_group_operations!(PodRistrettoPoint, CURVE25519_RISTRETTO);
+
+/// Perform a group operation using the `sol_curve_group_op` syscall.
+///
+/// For [`MUL`], `left` is the scalar and `right` is the point.
+#[inline(always)]
+fn group_op(
+    curve_id: u64,
+    group_op: u64,
+    left: &[u8; 32],
+    right: &[u8; 32],
+) -> Result<[u8; 32], Curve25519Error> {
+    #[cfg(target_os = "solana")]
+    {
+        let mut result = [0u8; 32];
+        let status = unsafe {
+            crate::syscalls::sol_curve_group_op(
+                curve_id,
+                group_op,
+                left.as_ptr(),
+                right.as_ptr(),
+                result.as_mut_ptr(),
+            )
+        };
+
+        match status {
+            crate::SUCCESS => Ok(result),
+            _ => Err(Curve25519Error::OperationFailed),
+        }
+    }
+
+    #[cfg(not(target_os = "solana"))]
+    {
+        core::hint::black_box((curve_id, group_op, left, right));
+        Err(Curve25519Error::OperationFailed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multiscalar_multiply_length() {
+        let scalars = [PodScalar::default(); 2];
+        let points = [PodEdwardsPoint::default(); 3];
+
+        assert_eq!(
+            PodEdwardsPoint::multiscalar_multiply(&scalars, &points),
+            Err(Curve25519Error::InvalidLength)
+        );
+
+        let scalars = [PodScalar::default(); MAX_MULTISCALAR_POINTS + 1];
+        let points = [PodRistrettoPoint::default(); MAX_MULTISCALAR_POINTS + 1];
+
+        assert_eq!(
+            PodRistrettoPoint::multiscalar_multiply(&scalars, &points),
+            Err(Curve25519Error::InvalidLength)
+        );
+    }
+
+    #[test]
+    fn test_off_chain() {
+        let point = PodEdwardsPoint::default();
+        let scalar = PodScalar::default();
+
+        assert!(!point.validate());
+        assert_eq!(point.add(&point), Err(Curve25519Error::OperationFailed));
+        assert_eq!(
+            point.subtract(&point),
+            Err(Curve25519Error::OperationFailed)
+        );
+        assert_eq!(
+            point.multiply(&scalar),
+            Err(Curve25519Error::OperationFailed)
+        );
+        assert_eq!(
+            PodEdwardsPoint::multiscalar_multiply(&[scalar], &[point]),
+            Err(Curve25519Error::OperationFailed)
+        );
+
+        let point = PodRistrettoPoint::default();
+
+        assert!(!point.validate());
+        assert_eq!(point.add(&point), Err(Curve25519Error::OperationFailed));
+        assert_eq!(
+            point.multiply(&scalar),
+            Err(Curve25519Error::OperationFailed)
+        );
+    }
+}
@@ -0,0 +1,18 @@
+//! Elliptic curve operations.
+//!
+//! The operations are performed by the runtime through syscalls:
+//!
+//! - [`curve25519`]: group operations on the Edwards and Ristretto representations
+//!   of Curve25519.
+//! - [`alt_bn128`]: group operations, pairing and point compression on the BN254
+//!   (`alt_bn128`) curve, following the encoding of [EIP-196] and [EIP-197].
+//!
+//! These operations are only available on target `solana`; off-chain, validations
+//! return `false` and operations return an error. Note that the `sol_curve_pairing_map`
+//! syscall is not wrapped, since it is not implemented by the runtime.
+//!
+//! [EIP-196]: https://eips.ethereum.org/EIPS/eip-196
+//! [EIP-197]: https://eips.ethereum.org/EIPS/eip-197
+
+pub mod alt_bn128;
+pub mod curve25519;
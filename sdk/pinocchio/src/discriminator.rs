@@ -0,0 +1,267 @@
+//! Instruction discriminators and dispatch.
+//!
+//! Programs usually prefix the instruction data with a discriminator identifying the
+//! instruction to execute. The [`dispatch!`] macro maps 1-, 4- or 8-byte
+//! discriminators to the instruction handlers; discriminators can be arbitrary
+//! constant expressions, including Anchor-style discriminators computed at compile
+//! time with [`anchor_discriminator`].
+//!
+//! [`dispatch!`]: crate::dispatch!
+
+/// Dispatch the instruction data to the handler of its discriminator.
+///
+/// The first argument is the instruction data and the second one is a list of
+/// `discriminator => handler` arms, preceded by the name bound to the instruction
+/// data following the discriminator. Discriminators are constant expressions of
+/// type `[u8; N]`, where `N` is either 1, 4 or 8 and must be the same for all arms.
+///
+/// The macro evaluates to the result of the handler of the matching discriminator,
+/// or to [`ProgramError::InvalidInstructionData`] if the instruction data does not
+/// start with any of the discriminators. Discriminators are computed at compile time
+/// and duplicated discriminators result in a compilation error.
+///
+/// Since handlers are arbitrary expressions, the macro can be used with both the
+/// [`entrypoint!`] and [`lazy_entrypoint!`] macros.
+///
+/// # Examples
+///
+/// ```
+/// use pinocchio::{
+///     account_info::AccountInfo, discriminator::anchor_discriminator, dispatch,
+///     pubkey::Pubkey, ProgramResult,
+/// };
+///
+/// pub fn process_instruction(
+///     program_id: &Pubkey,
+///     accounts: &[AccountInfo],
+///     instruction_data: &[u8],
+/// ) -> ProgramResult {
+///     dispatch!(instruction_data, |data| {
+///         anchor_discriminator("initialize") => process_initialize(accounts, data),
+///         anchor_discriminator("transfer") => process_transfer(accounts, data),
+///     })
+/// }
+///
+/// fn process_initialize(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+///     Ok(())
+/// }
+///
+/// fn process_transfer(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+///     Ok(())
+/// }
+/// ```
+///
+/// Duplicated discriminators do not compile:
+///
+/// ```compile_fail
+/// use pinocchio::{dispatch, ProgramResult};
+///
+/// fn process_instruction(instruction_data: &[u8]) -> ProgramResult {
+///     dispatch!(instruction_data, |data| {
+///         [0] => Ok(()),
+///         [0] => Ok(()),
+///     })
+/// }
+/// ```
+///
+/// [`ProgramError::InvalidInstructionData`]: crate::program_error::ProgramError::InvalidInstructionData
+/// [`entrypoint!`]: crate::entrypoint!
+/// [`lazy_entrypoint!`]: crate::lazy_entrypoint!
+#[macro_export]
+macro_rules! dispatch {
+    ( $instruction_data:expr, |$data:ident| { $( $discriminator:expr => $handler:expr ),+ $(,)? } ) => {{
+        const LEN: usize = $crate::discriminator::discriminator_len(&[$( $discriminator.len() ),+]);
+        const _: () = assert!(
+            !$crate::discriminator::has_duplicates(&[$(
+                $crate::discriminator::to_u64(&$discriminator)
+            ),+]),
+            "duplicated discriminator"
+        );
+
+        let instruction_data: &[u8] = $instruction_data;
+
+        if instruction_data.len() >= LEN {
+            let (discriminator, $data) = instruction_data.split_at(LEN);
+            let discriminator = $crate::discriminator::to_u64(discriminator);
+            $(
+                if discriminator == {
+                    const DISCRIMINATOR: u64 = $crate::discriminator::to_u64(&$discriminator);
+                    DISCRIMINATOR
+                } {
+                    $handler
+                } else
+            )+ {
+                Err($crate::program_error::ProgramError::InvalidInstructionData)
+            }
+        } else {
+            Err($crate::program_error::ProgramError::InvalidInstructionData)
+        }
+    }};
+}
+
+/// Namespace of the Anchor instruction discriminators.
+pub const ANCHOR_NAMESPACE: &str = "global";
+
+/// Return the Anchor discriminator of the instruction with the given name.
+///
+/// This is the sighash of the instruction name (in snake case) in the
+/// [`ANCHOR_NAMESPACE`]. It can be evaluated at compile time.
+#[inline(always)]
+pub const fn anchor_discriminator(name: &str) -> [u8; 8] {
+    sighash(ANCHOR_NAMESPACE, name)
+}
+
+/// Return the sighash of a name in the given namespace.
+///
+/// The sighash is the first 8 bytes of the SHA-256 hash of `"{namespace}:{name}"`.
+/// It can be evaluated at compile time.
+pub const fn sighash(namespace: &str, name: &str) -> [u8; 8] {
+    let hash = crate::hash::const_sha256::hashv(&[namespace.as_bytes(), b":", name.as_bytes()]);
+    let mut sighash = [0u8; 8];
+    let mut i = 0;
+
+    while i < sighash.len() {
+        sighash[i] = hash[i];
+        i += 1;
+    }
+
+    sighash
+}
+
+/// Return the common length of the discriminators.
+///
+/// # Panics
+///
+/// Panics if the discriminators do not have the same length or the length is not
+/// 1, 4 or 8 bytes.
+#[doc(hidden)]
+pub const fn discriminator_len(lengths: &[usize]) -> usize {
+    let len = lengths[0];
+    assert!(
+        len == 1 || len == 4 || len == 8,
+        "discriminators must be 1, 4 or 8 bytes long"
+    );
+
+    let mut i = 1;
+    while i < lengths.len() {
+        assert!(
+            lengths[i] == len,
+            "discriminators must have the same length"
+        );
+        i += 1;
+    }
+
+    len
+}
+
+/// Return the little-endian value of a discriminator of up to 8 bytes.
+#[doc(hidden)]
+#[inline(always)]
+pub const fn to_u64(discriminator: &[u8]) -> u64 {
+    let mut value = 0u64;
+    let mut i = 0;
+
+    while i < discriminator.len() {
+        value |= (discriminator[i] as u64) << (i * 8);
+        i += 1;
+    }
+
+    value
+}
+
+/// Indicates whether there are duplicated values.
+#[doc(hidden)]
+pub const fn has_duplicates(values: &[u64]) -> bool {
+    let mut i = 0;
+
+    while i < values.len() {
+        let mut j = i + 1;
+        while j < values.len() {
+            if values[i] == values[j] {
+                return true;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{hash::sha256v, program_error::ProgramError, ProgramResult};
+
+    fn process(instruction_data: &[u8]) -> Result<(u8, usize), ProgramError> {
+        dispatch!(instruction_data, |data| {
+            [0] => Ok((0, data.len())),
+            [1] => Ok((1, data.len())),
+            [255] => Err(ProgramError::Custom(255)),
+        })
+    }
+
+    #[test]
+    fn test_dispatch() {
+        assert_eq!(process(&[0]), Ok((0, 0)));
+        assert_eq!(process(&[1, 2, 3]), Ok((1, 2)));
+        assert_eq!(process(&[255]), Err(ProgramError::Custom(255)));
+        assert_eq!(process(&[2]), Err(ProgramError::InvalidInstructionData));
+        assert_eq!(process(&[]), Err(ProgramError::InvalidInstructionData));
+    }
+
+    #[test]
+    fn test_dispatch_anchor() {
+        fn process(instruction_data: &[u8]) -> ProgramResult {
+            dispatch!(instruction_data, |data| {
+                anchor_discriminator("initialize") => {
+                    assert_eq!(data, &[1]);
+                    Ok(())
+                },
+                anchor_discriminator("transfer") => Err(ProgramError::Custom(1)),
+                sighash("state", "update") => Err(ProgramError::Custom(2)),
+            })
+        }
+
+        // anchor discriminator of `initialize`
+        let initialize = [175, 175, 109, 31, 13, 152, 155, 237];
+
+        let mut instruction_data = [1u8; 9];
+        instruction_data[..8].copy_from_slice(&initialize);
+
+        assert_eq!(process(&instruction_data), Ok(()));
+        assert_eq!(
+            process(&anchor_discriminator("transfer")),
+            Err(ProgramError::Custom(1))
+        );
+        assert_eq!(
+            process(&initialize[..4]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+
+    #[test]
+    fn test_dispatch_u32() {
+        fn process(instruction_data: &[u8]) -> Result<u32, ProgramError> {
+            dispatch!(instruction_data, |_data| {
+                1u32.to_le_bytes() => Ok(1),
+                u32::MAX.to_le_bytes() => Ok(u32::MAX),
+            })
+        }
+
+        assert_eq!(process(&[1, 0, 0, 0]), Ok(1));
+        assert_eq!(process(&[255; 4]), Ok(u32::MAX));
+        assert_eq!(process(&[1]), Err(ProgramError::InvalidInstructionData));
+    }
+
+    #[test]
+    fn test_sighash() {
+        for name in [
+            "initialize",
+            "a_much_longer_instruction_name_spanning_two_sha256_blocks_of_data",
+        ] {
+            let hash = sha256v(&[ANCHOR_NAMESPACE.as_bytes(), b":", name.as_bytes()]);
+            assert_eq!(anchor_discriminator(name), hash[..8]);
+        }
+    }
+}
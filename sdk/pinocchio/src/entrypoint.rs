@@ -1,10 +1,13 @@
 //! Macros and functions for defining the program entrypoint and setting up
 //! global handlers.
 
-use core::{alloc::Layout, mem::size_of, ptr::null_mut, slice::from_raw_parts};
+use core::{
+    alloc::Layout, marker::PhantomData, mem::size_of, ptr::null_mut, slice::from_raw_parts,
+};
 
 use crate::{
     account_info::{Account, AccountInfo, MAX_PERMITTED_DATA_INCREASE},
+    lazy_entrypoint::{read_account, MaybeAccount},
     pubkey::Pubkey,
     BPF_ALIGN_OF_U128, NON_DUP_MARKER,
 };
@@ -15,6 +18,10 @@ pub const HEAP_START_ADDRESS: u64 = 0x300000000;
 /// Length of the heap memory region used for program heap.
 pub const HEAP_LENGTH: usize = 32 * 1024;
 
+/// Maximum length of the heap memory region that can be requested with the
+/// compute budget's `RequestHeapFrame` instruction.
+pub const MAX_HEAP_LENGTH: usize = 256 * 1024;
+
 #[deprecated(
     since = "0.6.0",
     note = "Use `ProgramResult` from the crate root instead"
@@ -53,7 +60,12 @@ pub const SUCCESS: u64 = super::SUCCESS;
 /// be ignored. When the maximum is not specified, the default is 64. This is currently the [maximum
 /// number of accounts] that a transaction may lock in a block.
 ///
+/// Programs that must never allocate can pass `no_allocator` as the last argument (e.g.,
+/// `entrypoint!(process_instruction, no_allocator)`) to set up the global allocator using the
+/// [`no_allocator`] macro instead.
+///
 /// [maximum number of accounts]: https://github.com/anza-xyz/agave/blob/ccabfcf84921977202fd06d3197cbcea83742133/runtime/src/bank.rs#L3207-L3219
+/// [`no_allocator`]: crate::no_allocator!
 ///
 /// # Examples
 ///
@@ -89,9 +101,28 @@ pub const SUCCESS: u64 = super::SUCCESS;
 #[macro_export]
 macro_rules! entrypoint {
     ( $process_instruction:ident ) => {
-        entrypoint!($process_instruction, { $crate::MAX_TX_ACCOUNTS });
+        $crate::entrypoint!($process_instruction, { $crate::MAX_TX_ACCOUNTS });
+    };
+    ( $process_instruction:ident, no_allocator ) => {
+        $crate::entrypoint!($process_instruction, { $crate::MAX_TX_ACCOUNTS }, no_allocator);
     };
     ( $process_instruction:ident, $maximum:expr ) => {
+        $crate::entrypoint!(@program $process_instruction, $maximum);
+        $crate::entrypoint!(@handlers);
+    };
+    ( $process_instruction:ident, $maximum:expr, no_allocator ) => {
+        $crate::entrypoint!(@program $process_instruction, $maximum);
+        $crate::entrypoint!(@handlers no_allocator);
+    };
+    ( @program $process_instruction:ident, $maximum:expr ) => {
+        $crate::entrypoint!(
+            @entrypoint $maximum,
+            |program_id, accounts, _remaining, instruction_data| {
+                $process_instruction(program_id, accounts, instruction_data)
+            }
+        );
+    };
+    ( @entrypoint $maximum:expr, $process_instruction:expr ) => {
         /// Program entrypoint.
         #[no_mangle]
         pub unsafe extern "C" fn entrypoint(input: *mut u8) -> u64 {
@@ -100,24 +131,97 @@ macro_rules! entrypoint {
             // create an array of uninitialized account infos
             let mut accounts = [UNINIT; $maximum];
 
-            let (program_id, count, instruction_data) =
-                $crate::entrypoint::deserialize::<$maximum>(input, &mut accounts);
+            let (program_id, count, remaining, instruction_data) =
+                $crate::entrypoint::deserialize_with_remaining::<$maximum>(input, &mut accounts);
 
             // call the program's entrypoint passing `count` account infos; we know that
             // they are initialized so we cast the pointer to a slice of `[AccountInfo]`
-            match $process_instruction(
-                &program_id,
+            match ($process_instruction)(
+                program_id,
                 core::slice::from_raw_parts(accounts.as_ptr() as _, count),
-                &instruction_data,
+                remaining,
+                instruction_data,
             ) {
                 Ok(()) => $crate::SUCCESS,
                 Err(error) => error.into(),
             }
         }
-
+    };
+    ( @handlers ) => {
         $crate::custom_heap_default!();
         $crate::custom_panic_default!();
     };
+    ( @handlers no_allocator ) => {
+        $crate::no_allocator!();
+        $crate::custom_panic_default!();
+    };
+}
+
+/// Declare the program entrypoint and set up global handlers, giving the program
+/// access to the accounts exceeding the maximum.
+///
+/// This macro is similar to [`entrypoint!`], but the accounts exceeding the maximum
+/// number of accounts are not ignored. Instead, they are passed to the program as a
+/// [`RemainingAccounts`] iterator, which reads them from the input buffer on demand.
+/// This allows a program to use a small maximum for the accounts it always expects
+/// while still being able to process (e.g., forward to another program) a variable
+/// number of additional accounts.
+///
+/// The first argument is the name of a function with this type signature:
+///
+/// ```ignore
+/// fn process_instruction(
+///     program_id: &Pubkey,          // Public key of the account the program was loaded into
+///     accounts: &[AccountInfo],     // Up to the maximum number of accounts
+///     remaining: RemainingAccounts, // Accounts exceeding the maximum
+///     instruction_data: &[u8],      // Serialized instruction-specific data
+/// ) -> ProgramResult;
+/// ```
+///
+/// The second argument is the maximum number of accounts read upfront. As with
+/// [`entrypoint!`], `no_allocator` can be passed as the last argument.
+///
+/// # Examples
+///
+/// ```no_run
+/// #[cfg(feature = "bpf-entrypoint")]
+/// pub mod entrypoint {
+///
+///     use pinocchio::{
+///         account_info::AccountInfo,
+///         entrypoint::RemainingAccounts,
+///         entrypoint_with_remaining,
+///         msg,
+///         pubkey::Pubkey,
+///         ProgramResult
+///     };
+///
+///     entrypoint_with_remaining!(process_instruction, 2);
+///
+///     pub fn process_instruction(
+///         program_id: &Pubkey,
+///         accounts: &[AccountInfo],
+///         remaining: RemainingAccounts,
+///         instruction_data: &[u8],
+///     ) -> ProgramResult {
+///         msg!("Forwarding {} accounts", remaining.len());
+///         Ok(())
+///     }
+///
+/// }
+/// ```
+///
+/// [`RemainingAccounts`]: crate::entrypoint::RemainingAccounts
+#[macro_export]
+macro_rules! entrypoint_with_remaining {
+    ( $process_instruction:ident, $maximum:expr ) => {
+        $crate::entrypoint!(@entrypoint $maximum, $process_instruction);
+        $crate::entrypoint!(@handlers);
+    };
+    ( $process_instruction:ident, $maximum:expr, no_allocator ) => {
+        $crate::entrypoint!(@entrypoint $maximum, $process_instruction);
+        $crate::entrypoint!(@handlers no_allocator);
+    };
 }
 
 /// Deserialize the input arguments.
@@ -130,12 +234,32 @@ pub unsafe fn deserialize<'a, const MAX_ACCOUNTS: usize>(
     input: *mut u8,
     accounts: &mut [core::mem::MaybeUninit<AccountInfo>],
 ) -> (&'a Pubkey, usize, &'a [u8]) {
+    let (program_id, processed, _, instruction_data) =
+        deserialize_with_remaining::<MAX_ACCOUNTS>(input, accounts);
+
+    (program_id, processed, instruction_data)
+}
+
+/// Deserialize the input arguments, including an iterator over the accounts
+/// exceeding `MAX_ACCOUNTS`.
+///
+/// This can only be called from the entrypoint function of a Solana program and with
+/// a buffer that was serialized by the runtime.
+#[allow(clippy::cast_ptr_alignment, clippy::missing_safety_doc)]
+#[inline(always)]
+pub unsafe fn deserialize_with_remaining<'a, const MAX_ACCOUNTS: usize>(
+    input: *mut u8,
+    accounts: &mut [core::mem::MaybeUninit<AccountInfo>],
+) -> (&'a Pubkey, usize, RemainingAccounts<'a>, &'a [u8]) {
     let mut offset: usize = 0;
 
     // total number of accounts present; it only process up to MAX_ACCOUNTS
     let total_accounts = *(input.add(offset) as *const u64) as usize;
     offset += core::mem::size_of::<u64>();
 
+    // offset of the first account exceeding MAX_ACCOUNTS
+    let mut remaining_offset = offset;
+
     let processed = if total_accounts > 0 {
         // number of accounts to process (limited to MAX_ACCOUNTS)
         let processed = core::cmp::min(total_accounts, MAX_ACCOUNTS);
@@ -165,6 +289,8 @@ pub unsafe fn deserialize<'a, const MAX_ACCOUNTS: usize>(
             }
         }
 
+        remaining_offset = offset;
+
         // process any remaining accounts to move the offset to the instruction
         // data (there is a duplication of logic but we avoid testing whether we
         // have space for the account or not)
@@ -198,9 +324,53 @@ pub unsafe fn deserialize<'a, const MAX_ACCOUNTS: usize>(
     // program id
     let program_id: &Pubkey = &*(input.add(offset) as *const Pubkey);
 
-    (program_id, processed, instruction_data)
+    let remaining = RemainingAccounts {
+        input,
+        offset: remaining_offset,
+        remaining: (total_accounts - processed) as u64,
+        _input: PhantomData,
+    };
+
+    (program_id, processed, remaining, instruction_data)
+}
+
+/// Iterator over the accounts exceeding the maximum number of accounts read by
+/// the entrypoint.
+///
+/// Accounts are read from the input buffer as the iterator advances. Duplicated
+/// accounts are represented by the index of the original account, which can be
+/// resolved using the accounts slice when it is lower than its length.
+pub struct RemainingAccounts<'a> {
+    /// Pointer to the runtime input buffer for the instruction.
+    input: *mut u8,
+
+    /// Memory offset of the next account on the input buffer.
+    offset: usize,
+
+    /// Number of remaining accounts.
+    remaining: u64,
+
+    /// The accounts are only valid while the input buffer lives.
+    _input: PhantomData<&'a mut [u8]>,
 }
 
+impl Iterator for RemainingAccounts<'_> {
+    type Item = MaybeAccount;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.remaining = self.remaining.checked_sub(1)?;
+        Some(unsafe { read_account(self.input, &mut self.offset) })
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining as usize, Some(self.remaining as usize))
+    }
+}
+
+impl ExactSizeIterator for RemainingAccounts<'_> {}
+
 #[macro_export]
 macro_rules! custom_panic_default {
     () => {
@@ -218,6 +388,26 @@ macro_rules! custom_panic_default {
     };
 }
 
+/// Set up the global allocator of the program.
+///
+/// Without arguments, this sets up a [`BumpAllocator`] over the default heap of
+/// [`HEAP_LENGTH`] bytes, unless the `custom-heap` feature is enabled. This is the
+/// allocator set up by the [`entrypoint!`] macro.
+///
+/// Programs requesting a larger heap with the compute budget's `RequestHeapFrame`
+/// instruction can specify the heap length and, optionally, the allocator type, which
+/// must have a `const fn new(start: usize, len: usize)` constructor. These forms are
+/// not affected by the `custom-heap` feature, so a program using the [`entrypoint!`]
+/// macro can enable the feature to disable the default allocator and set up its own:
+///
+/// ```ignore
+/// entrypoint!(process_instruction);
+/// custom_heap_default!(64 * 1024, pinocchio::entrypoint::RollbackAllocator);
+/// ```
+///
+/// The heap length must be a multiple of 1024 and at most [`MAX_HEAP_LENGTH`].
+///
+/// [`entrypoint!`]: crate::entrypoint!
 #[macro_export]
 macro_rules! custom_heap_default {
     () => {
@@ -231,14 +421,45 @@ macro_rules! custom_heap_default {
             len: $crate::entrypoint::HEAP_LENGTH,
         };
     };
+    ( $heap_length:expr ) => {
+        $crate::custom_heap_default!($heap_length, $crate::entrypoint::BumpAllocator);
+    };
+    ( $heap_length:expr, $allocator:ty ) => {
+        const _: () = assert!(
+            $heap_length <= $crate::entrypoint::MAX_HEAP_LENGTH
+                && ($heap_length as usize) % 1024 == 0,
+            "invalid heap length"
+        );
+
+        #[cfg(target_os = "solana")]
+        extern crate alloc;
+
+        #[cfg(target_os = "solana")]
+        #[global_allocator]
+        static A: $allocator = <$allocator>::new(
+            $crate::entrypoint::HEAP_START_ADDRESS as usize,
+            $heap_length,
+        );
+    };
 }
 
 /// The bump allocator used as the default rust heap when running programs.
+///
+/// Memory is allocated from the end of the heap towards its start and is never
+/// freed.
 pub struct BumpAllocator {
     pub start: usize,
     pub len: usize,
 }
 
+impl BumpAllocator {
+    /// Create an allocator for the heap region of `len` bytes at `start`.
+    #[inline(always)]
+    pub const fn new(start: usize, len: usize) -> Self {
+        Self { start, len }
+    }
+}
+
 /// Integer arithmetic in this global allocator implementation is safe when
 /// operating on the prescribed `HEAP_START_ADDRESS` and `HEAP_LENGTH`. Any
 /// other use may overflow and is thus unsupported and at one's own risk.
@@ -267,3 +488,277 @@ unsafe impl core::alloc::GlobalAlloc for BumpAllocator {
         // I'm a bump allocator, I don't free
     }
 }
+
+/// Set up a global allocator that does not allow heap allocations.
+///
+/// Any heap allocation aborts the program, logging a message. This is useful for programs
+/// that must never allocate, making accidental allocations (e.g., through `format!` or a
+/// `Vec`) fail loudly instead of consuming heap memory and compute units.
+///
+/// Enabling the `no-allocator-check` feature of this crate provides a best-effort check
+/// that fails earlier. In this case, the allocator calls the undefined function
+/// `pinocchio_program_allocates_with_no_allocator` instead of aborting. This is not a build
+/// failure: the SBF linker leaves undefined symbols to be resolved when the program is
+/// loaded (as it does for syscalls), so `cargo build-sbf` only reports a warning and the
+/// program then fails to load (or to execute the call, depending on the loader). The check
+/// relies on the allocator being removed as dead code when the program does not allocate,
+/// so it is only meaningful for optimized builds and can also be triggered by allocations
+/// that are never executed.
+///
+/// This macro is not affected by the `custom-heap` feature.
+#[macro_export]
+macro_rules! no_allocator {
+    () => {
+        #[cfg(target_os = "solana")]
+        extern crate alloc;
+
+        #[cfg(target_os = "solana")]
+        #[global_allocator]
+        static A: $crate::entrypoint::NoAllocator = $crate::entrypoint::NoAllocator;
+    };
+}
+
+/// A global allocator that does not allow heap allocations.
+///
+/// See the [`no_allocator`] macro for details.
+///
+/// [`no_allocator`]: crate::no_allocator!
+pub struct NoAllocator;
+
+unsafe impl core::alloc::GlobalAlloc for NoAllocator {
+    #[inline]
+    unsafe fn alloc(&self, _layout: Layout) -> *mut u8 {
+        #[cfg(all(target_os = "solana", feature = "no-allocator-check"))]
+        {
+            extern "C" {
+                fn pinocchio_program_allocates_with_no_allocator() -> !;
+            }
+            pinocchio_program_allocates_with_no_allocator();
+        }
+
+        #[cfg(all(target_os = "solana", not(feature = "no-allocator-check")))]
+        {
+            crate::log::sol_log("** NO ALLOCATOR: heap allocations are not supported **");
+            crate::syscalls::abort();
+        }
+
+        #[cfg(not(target_os = "solana"))]
+        null_mut()
+    }
+
+    #[inline]
+    unsafe fn dealloc(&self, _ptr: *mut u8, _layout: Layout) {
+        // no memory is ever allocated
+    }
+}
+
+/// A bump allocator that reclaims the memory of the most recent allocation.
+///
+/// Memory is allocated from the start of the heap towards its end. When the most
+/// recent allocation is freed, the memory is returned to the heap; reallocating it
+/// (e.g., growing a `Vec`) is done in place. This makes the allocator suitable for
+/// programs that use temporary or growing allocations, which would exhaust the heap
+/// with a [`BumpAllocator`]. Other allocations are not reclaimed until all more
+/// recent allocations are freed.
+///
+/// The current position is stored in the first bytes of the heap, which are
+/// zero-initialized by the runtime.
+pub struct RollbackAllocator {
+    pub start: usize,
+    pub len: usize,
+}
+
+impl RollbackAllocator {
+    /// Minimum size and alignment of allocations.
+    ///
+    /// Rounding allocations avoids alignment padding between them in most cases,
+    /// so that consecutive allocations can be rolled back.
+    const MIN_ALIGN: usize = 8;
+
+    /// Create an allocator for the heap region of `len` bytes at `start`.
+    #[inline(always)]
+    pub const fn new(start: usize, len: usize) -> Self {
+        Self { start, len }
+    }
+
+    /// Return a pointer to the current position.
+    #[inline(always)]
+    fn position(&self) -> *mut usize {
+        self.start as *mut usize
+    }
+
+    /// Return the end address of an allocation of `size` bytes at `ptr`.
+    #[inline(always)]
+    fn end_of(ptr: usize, size: usize) -> Option<usize> {
+        ptr.checked_add(size.checked_next_multiple_of(Self::MIN_ALIGN)?)
+    }
+}
+
+/// Integer arithmetic in this global allocator implementation is safe when
+/// operating on the prescribed `HEAP_START_ADDRESS` and heap length. Any
+/// other use may overflow and is thus unsupported and at one's own risk.
+#[allow(clippy::arithmetic_side_effects)]
+unsafe impl core::alloc::GlobalAlloc for RollbackAllocator {
+    #[inline]
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let mut pos = *self.position();
+        if pos == 0 {
+            // First time, set starting position after the position itself.
+            pos = self.start + size_of::<usize>();
+        }
+
+        let align = core::cmp::max(layout.align(), Self::MIN_ALIGN);
+        let Some(ptr) = pos.checked_next_multiple_of(align) else {
+            return null_mut();
+        };
+
+        match Self::end_of(ptr, layout.size()) {
+            Some(end) if end <= self.start + self.len => {
+                *self.position() = end;
+                ptr as *mut u8
+            }
+            _ => null_mut(),
+        }
+    }
+
+    #[inline]
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // only the most recent allocation can be reclaimed
+        if Self::end_of(ptr as usize, layout.size()) == Some(*self.position()) {
+            *self.position() = ptr as usize;
+        }
+    }
+
+    #[inline]
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if Self::end_of(ptr as usize, layout.size()) == Some(*self.position()) {
+            // the most recent allocation is resized in place
+            match Self::end_of(ptr as usize, new_size) {
+                Some(end) if end <= self.start + self.len => {
+                    *self.position() = end;
+                    ptr
+                }
+                _ => null_mut(),
+            }
+        } else {
+            let new_ptr = self.alloc(Layout::from_size_align_unchecked(new_size, layout.align()));
+
+            if !new_ptr.is_null() {
+                core::ptr::copy_nonoverlapping(
+                    ptr,
+                    new_ptr,
+                    core::cmp::min(layout.size(), new_size),
+                );
+            }
+
+            new_ptr
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::alloc::GlobalAlloc;
+
+    use super::*;
+
+    #[test]
+    fn test_rollback_allocator() {
+        let mut heap = [0u64; 16];
+        let start = heap.as_mut_ptr() as usize;
+        let allocator = RollbackAllocator::new(start, 128);
+
+        unsafe {
+            let layout = Layout::from_size_align_unchecked(12, 4);
+            let first = allocator.alloc(layout);
+            assert_eq!(first as usize, start + 8);
+
+            // allocations are rounded to 8 bytes
+            let second = allocator.alloc(layout);
+            assert_eq!(second as usize, start + 24);
+
+            // the most recent allocation is reclaimed
+            allocator.dealloc(second, layout);
+            assert_eq!(allocator.alloc(layout), second);
+
+            // other allocations are not reclaimed
+            allocator.dealloc(first, layout);
+            let third = allocator.alloc(layout);
+            assert_eq!(third as usize, start + 40);
+
+            // the most recent allocation is resized in place
+            let grown = allocator.realloc(third, layout, 88);
+            assert_eq!(grown, third);
+            assert!(allocator
+                .alloc(Layout::from_size_align_unchecked(8, 8))
+                .is_null());
+
+            // other allocations are moved, failing when the heap is exhausted
+            *first = 7;
+            assert!(allocator.realloc(first, layout, 16).is_null());
+
+            // freeing the most recent allocations makes the previous one the most recent
+            allocator.dealloc(grown, Layout::from_size_align_unchecked(88, 4));
+            assert_eq!(allocator.realloc(second, layout, 24), second);
+
+            let moved = allocator.realloc(first, layout, 16);
+            assert_eq!(moved as usize, start + 48);
+            assert_eq!(*moved, 7);
+
+            // allocations are aligned
+            let aligned = allocator.alloc(Layout::from_size_align_unchecked(1, 32));
+            assert_eq!(aligned as usize % 32, 0);
+        }
+    }
+
+    #[test]
+    fn test_no_allocator() {
+        unsafe {
+            let layout = Layout::from_size_align_unchecked(8, 8);
+
+            assert!(NoAllocator.alloc(layout).is_null());
+            assert!(NoAllocator.alloc_zeroed(layout).is_null());
+
+            // nothing is ever allocated, so freeing and growing memory is a no-op
+            let mut value = 7u64;
+            let ptr = &mut value as *mut u64 as *mut u8;
+            NoAllocator.dealloc(ptr, layout);
+            assert!(NoAllocator.realloc(ptr, layout, 16).is_null());
+            assert_eq!(value, 7);
+        }
+    }
+
+    #[test]
+    fn test_no_allocator_off_chain() {
+        mod program {
+            crate::no_allocator!();
+        }
+
+        extern crate alloc;
+
+        // the global allocator is only set up on target `solana`
+        let values = alloc::vec![1u8, 2, 3];
+        assert_eq!(values.len(), 3);
+    }
+
+    #[test]
+    fn test_bump_allocator() {
+        let mut heap = [0u64; 16];
+        let start = heap.as_mut_ptr() as usize;
+        let allocator = BumpAllocator::new(start, 128);
+
+        unsafe {
+            let layout = Layout::from_size_align_unchecked(16, 8);
+            let first = allocator.alloc(layout);
+            assert_eq!(first as usize, start + 112);
+
+            // memory is never freed
+            allocator.dealloc(first, layout);
+            assert_eq!(allocator.alloc(layout) as usize, start + 96);
+
+            assert!(allocator
+                .alloc(Layout::from_size_align_unchecked(128, 8))
+                .is_null());
+        }
+    }
+}
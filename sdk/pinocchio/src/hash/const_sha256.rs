@@ -0,0 +1,147 @@
+//! `const` implementation of SHA-256.
+//!
+//! This is used to compute discriminators at compile time and, off-chain, by the
+//! host implementation of [`sha256v`](super::sha256v). On-chain, digests computed
+//! at runtime should use the syscall instead.
+
+use super::{Hash, HASH_BYTES};
+
+/// Round constants for SHA-256.
+const SHA256_K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// Initial hash value for SHA-256.
+const SHA256_IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// Return the SHA-256 hash of the concatenation of `parts`.
+pub(crate) const fn hashv(parts: &[&[u8]]) -> Hash {
+    let mut len = 0;
+    let mut i = 0;
+    while i < parts.len() {
+        len += parts[i].len();
+        i += 1;
+    }
+
+    // message, 0x80 marker and 8-byte length, padded to a multiple of 64 bytes
+    let blocks = (len + 9).div_ceil(64);
+    let mut state = SHA256_IV;
+    let mut block_index = 0;
+
+    while block_index < blocks {
+        let mut block = [0u8; 64];
+        let mut j = 0;
+
+        while j < 64 {
+            let position = block_index * 64 + j;
+
+            block[j] = if position < len {
+                byte_at(parts, position)
+            } else if position == len {
+                0x80
+            } else if position >= blocks * 64 - 8 {
+                ((len as u64 * 8) >> ((blocks * 64 - 1 - position) * 8)) as u8
+            } else {
+                0
+            };
+
+            j += 1;
+        }
+
+        state = compress(state, &block);
+        block_index += 1;
+    }
+
+    let mut hash = [0u8; HASH_BYTES];
+    let mut i = 0;
+    while i < 8 {
+        let bytes = state[i].to_be_bytes();
+        hash[i * 4] = bytes[0];
+        hash[i * 4 + 1] = bytes[1];
+        hash[i * 4 + 2] = bytes[2];
+        hash[i * 4 + 3] = bytes[3];
+        i += 1;
+    }
+
+    hash
+}
+
+/// Return the byte at `position` of the concatenation of `parts`.
+const fn byte_at(parts: &[&[u8]], mut position: usize) -> u8 {
+    let mut i = 0;
+    while position >= parts[i].len() {
+        position -= parts[i].len();
+        i += 1;
+    }
+    parts[i][position]
+}
+
+/// Apply the SHA-256 compression function to a block.
+const fn compress(state: [u32; 8], block: &[u8; 64]) -> [u32; 8] {
+    let mut w = [0u32; 64];
+    let mut i = 0;
+    while i < 16 {
+        w[i] = u32::from_be_bytes([
+            block[i * 4],
+            block[i * 4 + 1],
+            block[i * 4 + 2],
+            block[i * 4 + 3],
+        ]);
+        i += 1;
+    }
+    while i < 64 {
+        let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+        let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+        w[i] = w[i - 16]
+            .wrapping_add(s0)
+            .wrapping_add(w[i - 7])
+            .wrapping_add(s1);
+        i += 1;
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+
+    let mut i = 0;
+    while i < 64 {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = h
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(SHA256_K[i])
+            .wrapping_add(w[i]);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+        i += 1;
+    }
+
+    [
+        state[0].wrapping_add(a),
+        state[1].wrapping_add(b),
+        state[2].wrapping_add(c),
+        state[3].wrapping_add(d),
+        state[4].wrapping_add(e),
+        state[5].wrapping_add(f),
+        state[6].wrapping_add(g),
+        state[7].wrapping_add(h),
+    ]
+}
@@ -0,0 +1,794 @@
+//! Host implementation of the hash functions.
+//!
+//! These are straightforward (non-optimized) implementations used when the program
+//! is not running on the SBF target, so that unit tests produce the same digests as
+//! the runtime.
+
+use super::{
+    poseidon::{Endianness, PoseidonError, MAX_INPUTS},
+    Hash, HASH_BYTES,
+};
+
+/// Return the SHA-256 hash of the concatenation of `vals`.
+pub fn sha256(vals: &[&[u8]]) -> Hash {
+    super::const_sha256::hashv(vals)
+}
+
+/// Return the Keccak-256 hash of the concatenation of `vals`.
+pub fn keccak256(vals: &[&[u8]]) -> Hash {
+    let mut hasher = Keccak256::default();
+    vals.iter().for_each(|val| hasher.update(val));
+    hasher.finalize()
+}
+
+/// Return the BLAKE3 hash of the concatenation of `vals`.
+pub fn blake3(vals: &[&[u8]]) -> Hash {
+    let mut hasher = Blake3::default();
+    vals.iter().for_each(|val| hasher.update(val));
+    hasher.finalize()
+}
+
+/// Return the Poseidon hash (BN254, x^5 S-boxes, Circom parameters) of `vals`.
+///
+/// The number and length of the inputs must have been validated by the caller.
+pub fn poseidon(endianness: Endianness, vals: &[&[u8]]) -> Result<Hash, PoseidonError> {
+    let width = vals.len() + 1;
+    let partial_rounds = POSEIDON_PARTIAL_ROUNDS[width - 2];
+    let rounds = POSEIDON_FULL_ROUNDS + partial_rounds;
+
+    // the first element of the state is the domain tag, which is always zero
+    let mut state = [[0u64; 4]; POSEIDON_MAX_WIDTH];
+
+    for (element, val) in state[1..width].iter_mut().zip(vals) {
+        let mut bytes = [0u8; HASH_BYTES];
+        match endianness {
+            Endianness::BigEndian => {
+                bytes[HASH_BYTES - val.len()..].copy_from_slice(val);
+                bytes.reverse();
+            }
+            Endianness::LittleEndian => bytes[..val.len()].copy_from_slice(val),
+        }
+
+        let mut value = [0u64; 4];
+        for (limb, chunk) in value.iter_mut().zip(bytes.chunks_exact(8)) {
+            *limb = u64::from_le_bytes(chunk.try_into().unwrap());
+        }
+
+        if !fr_lt(&value, &FR_MODULUS) {
+            return Err(PoseidonError::InputLargerThanModulus);
+        }
+        *element = fr_to_montgomery(&value);
+    }
+
+    let state = &mut state[..width];
+
+    // the parameters are generated as a single stream: all round constants
+    // first, followed by the MDS matrix; the matrix is needed by every round,
+    // so it is generated upfront and the round constants are streamed from a
+    // second generator
+    let mut grain = Grain::new(width, partial_rounds);
+    (0..rounds * width).for_each(|_| {
+        grain.field_element();
+    });
+    let mds = poseidon_mds(&mut grain, width);
+
+    let mut grain = Grain::new(width, partial_rounds);
+    let half_full_rounds = POSEIDON_FULL_ROUNDS / 2;
+
+    for round in 0..rounds {
+        for element in state.iter_mut() {
+            *element = fr_add(element, &fr_to_montgomery(&grain.field_element()));
+        }
+
+        if round < half_full_rounds || round >= half_full_rounds + partial_rounds {
+            state
+                .iter_mut()
+                .for_each(|element| *element = fr_pow5(element));
+        } else {
+            state[0] = fr_pow5(&state[0]);
+        }
+
+        let mut mixed = [[0u64; 4]; POSEIDON_MAX_WIDTH];
+        for (row, element) in mds.iter().zip(mixed.iter_mut()).take(width) {
+            for (entry, value) in row.iter().zip(state.iter()) {
+                *element = fr_add(element, &fr_mul(entry, value));
+            }
+        }
+        state.copy_from_slice(&mixed[..width]);
+    }
+
+    let mut hash = [0u8; HASH_BYTES];
+    for (chunk, limb) in hash
+        .chunks_exact_mut(8)
+        .zip(fr_from_montgomery(&state[0]).iter())
+    {
+        chunk.copy_from_slice(&limb.to_le_bytes());
+    }
+
+    if endianness == Endianness::BigEndian {
+        hash.reverse();
+    }
+
+    Ok(hash)
+}
+
+// Keccak-256
+
+/// Round constants for the Keccak-f[1600] permutation.
+const KECCAK_RC: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808a,
+    0x8000000080008000,
+    0x000000000000808b,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008a,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000a,
+    0x000000008000808b,
+    0x800000000000008b,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800a,
+    0x800000008000000a,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// Rotation offsets for the Keccak-f[1600] permutation, in the order of the `pi` step.
+const KECCAK_RHO: [u32; 24] = [
+    1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44,
+];
+
+/// Lane indices visited by the `pi` step of the Keccak-f[1600] permutation.
+const KECCAK_PI: [usize; 24] = [
+    10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1,
+];
+
+/// Number of bytes absorbed per permutation for a 256-bit output.
+const KECCAK_RATE: usize = 136;
+
+#[derive(Default)]
+struct Keccak256 {
+    state: [u64; 25],
+    offset: usize,
+}
+
+impl Keccak256 {
+    fn update(&mut self, data: &[u8]) {
+        for byte in data {
+            self.xor_byte(self.offset, *byte);
+            self.offset += 1;
+
+            if self.offset == KECCAK_RATE {
+                keccak_f(&mut self.state);
+                self.offset = 0;
+            }
+        }
+    }
+
+    fn finalize(mut self) -> Hash {
+        // original Keccak padding (not the SHA-3 domain separation)
+        self.xor_byte(self.offset, 0x01);
+        self.xor_byte(KECCAK_RATE - 1, 0x80);
+        keccak_f(&mut self.state);
+
+        let mut hash = [0u8; HASH_BYTES];
+        for (chunk, lane) in hash.chunks_exact_mut(8).zip(self.state.iter()) {
+            chunk.copy_from_slice(&lane.to_le_bytes());
+        }
+        hash
+    }
+
+    #[inline(always)]
+    fn xor_byte(&mut self, index: usize, byte: u8) {
+        self.state[index / 8] ^= (byte as u64) << (8 * (index % 8));
+    }
+}
+
+fn keccak_f(state: &mut [u64; 25]) {
+    for rc in KECCAK_RC {
+        // theta
+        let mut c = [0u64; 5];
+        for (x, column) in c.iter_mut().enumerate() {
+            *column = state[x] ^ state[x + 5] ^ state[x + 10] ^ state[x + 15] ^ state[x + 20];
+        }
+        for x in 0..5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                state[x + 5 * y] ^= d;
+            }
+        }
+
+        // rho and pi
+        let mut last = state[1];
+        for (rho, pi) in KECCAK_RHO.iter().zip(KECCAK_PI.iter()) {
+            let current = state[*pi];
+            state[*pi] = last.rotate_left(*rho);
+            last = current;
+        }
+
+        // chi
+        for y in 0..5 {
+            let row = [
+                state[5 * y],
+                state[5 * y + 1],
+                state[5 * y + 2],
+                state[5 * y + 3],
+                state[5 * y + 4],
+            ];
+            for x in 0..5 {
+                state[5 * y + x] = row[x] ^ (!row[(x + 1) % 5] & row[(x + 2) % 5]);
+            }
+        }
+
+        // iota
+        state[0] ^= rc;
+    }
+}
+
+// BLAKE3
+
+/// Initialization vector for BLAKE3.
+const BLAKE3_IV: [u32; 8] = [
+    0x6A09E667, 0xBB67AE85, 0x3C6EF372, 0xA54FF53A, 0x510E527F, 0x9B05688C, 0x1F83D9AB, 0x5BE0CD19,
+];
+
+/// Message word permutation applied between rounds.
+const BLAKE3_MSG_PERMUTATION: [usize; 16] = [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8];
+
+const BLAKE3_BLOCK_LEN: usize = 64;
+const BLAKE3_CHUNK_LEN: usize = 1024;
+
+const CHUNK_START: u32 = 1 << 0;
+const CHUNK_END: u32 = 1 << 1;
+const PARENT: u32 = 1 << 2;
+const ROOT: u32 = 1 << 3;
+
+/// Maximum depth of the chaining value stack (enough for inputs up to 2^64 bytes).
+const BLAKE3_MAX_DEPTH: usize = 54;
+
+#[inline(always)]
+fn g(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize, mx: u32, my: u32) {
+    state[a] = state[a].wrapping_add(state[b]).wrapping_add(mx);
+    state[d] = (state[d] ^ state[a]).rotate_right(16);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_right(12);
+    state[a] = state[a].wrapping_add(state[b]).wrapping_add(my);
+    state[d] = (state[d] ^ state[a]).rotate_right(8);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_right(7);
+}
+
+fn blake3_compress(
+    chaining_value: &[u32; 8],
+    block_words: &[u32; 16],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+) -> [u32; 16] {
+    let mut state = [
+        chaining_value[0],
+        chaining_value[1],
+        chaining_value[2],
+        chaining_value[3],
+        chaining_value[4],
+        chaining_value[5],
+        chaining_value[6],
+        chaining_value[7],
+        BLAKE3_IV[0],
+        BLAKE3_IV[1],
+        BLAKE3_IV[2],
+        BLAKE3_IV[3],
+        counter as u32,
+        (counter >> 32) as u32,
+        block_len,
+        flags,
+    ];
+    let mut m = *block_words;
+
+    for round in 0..7 {
+        g(&mut state, 0, 4, 8, 12, m[0], m[1]);
+        g(&mut state, 1, 5, 9, 13, m[2], m[3]);
+        g(&mut state, 2, 6, 10, 14, m[4], m[5]);
+        g(&mut state, 3, 7, 11, 15, m[6], m[7]);
+        g(&mut state, 0, 5, 10, 15, m[8], m[9]);
+        g(&mut state, 1, 6, 11, 12, m[10], m[11]);
+        g(&mut state, 2, 7, 8, 13, m[12], m[13]);
+        g(&mut state, 3, 4, 9, 14, m[14], m[15]);
+
+        if round < 6 {
+            let mut permuted = [0u32; 16];
+            for (i, index) in BLAKE3_MSG_PERMUTATION.iter().enumerate() {
+                permuted[i] = m[*index];
+            }
+            m = permuted;
+        }
+    }
+
+    for i in 0..8 {
+        state[i] ^= state[i + 8];
+        state[i + 8] ^= chaining_value[i];
+    }
+
+    state
+}
+
+#[inline(always)]
+fn first_8_words(words: [u32; 16]) -> [u32; 8] {
+    let mut output = [0u32; 8];
+    output.copy_from_slice(&words[..8]);
+    output
+}
+
+fn block_words(block: &[u8; BLAKE3_BLOCK_LEN]) -> [u32; 16] {
+    let mut words = [0u32; 16];
+    for (word, chunk) in words.iter_mut().zip(block.chunks_exact(4)) {
+        *word = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+    words
+}
+
+/// The input of a compression that has not been performed yet.
+///
+/// The last compression of the input needs to know whether it is the root node or not,
+/// so the output is kept around until the hash is finalized.
+struct Output {
+    input_chaining_value: [u32; 8],
+    block_words: [u32; 16],
+    counter: u64,
+    block_len: u32,
+    flags: u32,
+}
+
+impl Output {
+    fn chaining_value(&self) -> [u32; 8] {
+        first_8_words(blake3_compress(
+            &self.input_chaining_value,
+            &self.block_words,
+            self.counter,
+            self.block_len,
+            self.flags,
+        ))
+    }
+
+    fn root_hash(&self) -> Hash {
+        let words = blake3_compress(
+            &self.input_chaining_value,
+            &self.block_words,
+            0,
+            self.block_len,
+            self.flags | ROOT,
+        );
+
+        let mut hash = [0u8; HASH_BYTES];
+        for (chunk, word) in hash.chunks_exact_mut(4).zip(words.iter()) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        hash
+    }
+}
+
+fn parent_output(left: [u32; 8], right: [u32; 8]) -> Output {
+    let mut block_words = [0u32; 16];
+    block_words[..8].copy_from_slice(&left);
+    block_words[8..].copy_from_slice(&right);
+
+    Output {
+        input_chaining_value: BLAKE3_IV,
+        block_words,
+        counter: 0,
+        block_len: BLAKE3_BLOCK_LEN as u32,
+        flags: PARENT,
+    }
+}
+
+struct ChunkState {
+    chaining_value: [u32; 8],
+    chunk_counter: u64,
+    block: [u8; BLAKE3_BLOCK_LEN],
+    block_len: usize,
+    blocks_compressed: usize,
+}
+
+impl ChunkState {
+    fn new(chunk_counter: u64) -> Self {
+        Self {
+            chaining_value: BLAKE3_IV,
+            chunk_counter,
+            block: [0; BLAKE3_BLOCK_LEN],
+            block_len: 0,
+            blocks_compressed: 0,
+        }
+    }
+
+    fn len(&self) -> usize {
+        BLAKE3_BLOCK_LEN * self.blocks_compressed + self.block_len
+    }
+
+    fn start_flag(&self) -> u32 {
+        if self.blocks_compressed == 0 {
+            CHUNK_START
+        } else {
+            0
+        }
+    }
+
+    fn update(&mut self, mut input: &[u8]) {
+        while !input.is_empty() {
+            // only compress a full block once there is more input, since the last
+            // block of the chunk needs the `CHUNK_END` flag
+            if self.block_len == BLAKE3_BLOCK_LEN {
+                self.chaining_value = first_8_words(blake3_compress(
+                    &self.chaining_value,
+                    &block_words(&self.block),
+                    self.chunk_counter,
+                    BLAKE3_BLOCK_LEN as u32,
+                    self.start_flag(),
+                ));
+                self.blocks_compressed += 1;
+                self.block = [0; BLAKE3_BLOCK_LEN];
+                self.block_len = 0;
+            }
+
+            let length = core::cmp::min(BLAKE3_BLOCK_LEN - self.block_len, input.len());
+            self.block[self.block_len..self.block_len + length].copy_from_slice(&input[..length]);
+            self.block_len += length;
+            input = &input[length..];
+        }
+    }
+
+    fn output(&self) -> Output {
+        Output {
+            input_chaining_value: self.chaining_value,
+            block_words: block_words(&self.block),
+            counter: self.chunk_counter,
+            block_len: self.block_len as u32,
+            flags: self.start_flag() | CHUNK_END,
+        }
+    }
+}
+
+struct Blake3 {
+    chunk_state: ChunkState,
+    cv_stack: [[u32; 8]; BLAKE3_MAX_DEPTH],
+    cv_stack_len: usize,
+}
+
+impl Default for Blake3 {
+    fn default() -> Self {
+        Self {
+            chunk_state: ChunkState::new(0),
+            cv_stack: [[0; 8]; BLAKE3_MAX_DEPTH],
+            cv_stack_len: 0,
+        }
+    }
+}
+
+impl Blake3 {
+    fn push_stack(&mut self, chaining_value: [u32; 8]) {
+        self.cv_stack[self.cv_stack_len] = chaining_value;
+        self.cv_stack_len += 1;
+    }
+
+    fn pop_stack(&mut self) -> [u32; 8] {
+        self.cv_stack_len -= 1;
+        self.cv_stack[self.cv_stack_len]
+    }
+
+    fn add_chunk_chaining_value(&mut self, mut new_cv: [u32; 8], mut total_chunks: u64) {
+        // merge completed subtrees: each trailing zero bit in the number of chunks
+        // represents a subtree that can be merged with the new chaining value
+        while total_chunks & 1 == 0 {
+            new_cv = parent_output(self.pop_stack(), new_cv).chaining_value();
+            total_chunks >>= 1;
+        }
+        self.push_stack(new_cv);
+    }
+
+    fn update(&mut self, mut input: &[u8]) {
+        while !input.is_empty() {
+            // only finalize a full chunk once there is more input, since the last
+            // chunk might be the root
+            if self.chunk_state.len() == BLAKE3_CHUNK_LEN {
+                let chunk_cv = self.chunk_state.output().chaining_value();
+                let total_chunks = self.chunk_state.chunk_counter + 1;
+                self.add_chunk_chaining_value(chunk_cv, total_chunks);
+                self.chunk_state = ChunkState::new(total_chunks);
+            }
+
+            let length = core::cmp::min(BLAKE3_CHUNK_LEN - self.chunk_state.len(), input.len());
+            self.chunk_state.update(&input[..length]);
+            input = &input[length..];
+        }
+    }
+
+    fn finalize(&self) -> Hash {
+        let mut output = self.chunk_state.output();
+        let mut remaining = self.cv_stack_len;
+
+        while remaining > 0 {
+            remaining -= 1;
+            output = parent_output(self.cv_stack[remaining], output.chaining_value());
+        }
+
+        output.root_hash()
+    }
+}
+
+// Poseidon
+
+/// Modulus of the BN254 scalar field, as little-endian 64-bit limbs.
+const FR_MODULUS: [u64; 4] = [
+    0x43e1f593f0000001,
+    0x2833e84879b97091,
+    0xb85045b68181585d,
+    0x30644e72e131a029,
+];
+
+/// `-FR_MODULUS^-1 mod 2^64`, used by the Montgomery reduction.
+const FR_INV: u64 = 0xc2e1f593efffffff;
+
+/// `R^2 mod FR_MODULUS` (with `R = 2^256`), used to convert into Montgomery form.
+const FR_R2: [u64; 4] = [
+    0x1bb8e645ae216da7,
+    0x53fe3ab1e35c59e3,
+    0x8c49833d53bb8085,
+    0x0216d0b17f4e44a5,
+];
+
+/// Number of full rounds of the Circom parameters.
+const POSEIDON_FULL_ROUNDS: usize = 8;
+
+/// Number of partial rounds of the Circom parameters, indexed by `width - 2`.
+const POSEIDON_PARTIAL_ROUNDS: [usize; MAX_INPUTS] =
+    [56, 57, 56, 60, 60, 63, 64, 63, 60, 66, 60, 65];
+
+/// Maximum width of the permutation: the domain tag and [`MAX_INPUTS`] elements.
+const POSEIDON_MAX_WIDTH: usize = MAX_INPUTS + 1;
+
+/// Grain LFSR used to generate the Poseidon parameters.
+///
+/// This follows `generate_parameters_grain.sage` from the Poseidon reference
+/// implementation, which is the script used to generate the Circom parameters.
+struct Grain {
+    /// The 80-bit state, where bit `0` is the oldest bit.
+    state: u128,
+}
+
+impl Grain {
+    fn new(width: usize, partial_rounds: usize) -> Self {
+        // prime field (2 bits), x^alpha S-box (4 bits), field size (12 bits),
+        // width (12 bits), full rounds (10 bits), partial rounds (10 bits) and
+        // 30 bits set to one
+        let fields = [
+            (1, 2),
+            (0, 4),
+            (254, 12),
+            (width, 12),
+            (POSEIDON_FULL_ROUNDS, 10),
+            (partial_rounds, 10),
+            ((1 << 30) - 1, 30),
+        ];
+
+        let mut state = 0u128;
+        let mut position = 0;
+
+        for (value, bits) in fields {
+            for bit in (0..bits).rev() {
+                state |= (((value >> bit) & 1) as u128) << position;
+                position += 1;
+            }
+        }
+
+        let mut grain = Self { state };
+        // discard the first 160 bits
+        (0..160).for_each(|_| {
+            grain.step();
+        });
+        grain
+    }
+
+    fn step(&mut self) -> bool {
+        let state = self.state;
+        let bit =
+            (state ^ (state >> 13) ^ (state >> 23) ^ (state >> 38) ^ (state >> 51) ^ (state >> 62))
+                & 1;
+        self.state = (state >> 1) | (bit << 79);
+        bit == 1
+    }
+
+    fn next_bit(&mut self) -> bool {
+        // self-shrinking: bits are taken in pairs and the second bit is only
+        // output when the first one is set
+        loop {
+            let first = self.step();
+            let second = self.step();
+
+            if first {
+                return second;
+            }
+        }
+    }
+
+    /// Return the next 254 bits (most significant bit first) as little-endian limbs.
+    fn next_bits(&mut self) -> [u64; 4] {
+        let mut value = [0u64; 4];
+
+        for _ in 0..254 {
+            let bit = self.next_bit() as u64;
+            value[3] = (value[3] << 1) | (value[2] >> 63);
+            value[2] = (value[2] << 1) | (value[1] >> 63);
+            value[1] = (value[1] << 1) | (value[0] >> 63);
+            value[0] = (value[0] << 1) | bit;
+        }
+
+        value
+    }
+
+    /// Return the next field element, rejecting values that are not smaller
+    /// than the modulus.
+    fn field_element(&mut self) -> [u64; 4] {
+        loop {
+            let value = self.next_bits();
+
+            if fr_lt(&value, &FR_MODULUS) {
+                return value;
+            }
+        }
+    }
+}
+
+/// Generate the Cauchy MDS matrix (in Montgomery form) from the generator.
+///
+/// The reference script also rejects matrices that fail its security checks,
+/// which never happens for the supported widths: the first matrix with distinct
+/// `x` and `y` values is the one used by the Circom parameters.
+fn poseidon_mds(
+    grain: &mut Grain,
+    width: usize,
+) -> [[[u64; 4]; POSEIDON_MAX_WIDTH]; POSEIDON_MAX_WIDTH] {
+    let mut mds = [[[0u64; 4]; POSEIDON_MAX_WIDTH]; POSEIDON_MAX_WIDTH];
+
+    'sample: loop {
+        let mut values = [[0u64; 4]; 2 * POSEIDON_MAX_WIDTH];
+        let values = &mut values[..2 * width];
+
+        for value in values.iter_mut() {
+            // values have 254 bits, so a single subtraction reduces them
+            *value = fr_reduce(&grain.next_bits());
+        }
+
+        for (i, value) in values.iter().enumerate() {
+            if values[i + 1..].contains(value) {
+                continue 'sample;
+            }
+        }
+
+        let (xs, ys) = values.split_at(width);
+
+        for (row, x) in mds.iter_mut().zip(xs) {
+            for (entry, y) in row.iter_mut().zip(ys) {
+                let sum = fr_add(x, y);
+
+                if sum == [0; 4] {
+                    continue 'sample;
+                }
+                *entry = fr_inverse(&fr_to_montgomery(&sum));
+            }
+        }
+
+        return mds;
+    }
+}
+
+fn fr_lt(a: &[u64; 4], b: &[u64; 4]) -> bool {
+    for i in (0..4).rev() {
+        if a[i] != b[i] {
+            return a[i] < b[i];
+        }
+    }
+    false
+}
+
+/// Subtract the modulus if `a` is not smaller than it.
+fn fr_reduce(a: &[u64; 4]) -> [u64; 4] {
+    if fr_lt(a, &FR_MODULUS) {
+        return *a;
+    }
+
+    let mut result = [0u64; 4];
+    let mut borrow = false;
+
+    for (i, limb) in result.iter_mut().enumerate() {
+        let (value, overflow_1) = a[i].overflowing_sub(FR_MODULUS[i]);
+        let (value, overflow_2) = value.overflowing_sub(borrow as u64);
+        *limb = value;
+        borrow = overflow_1 || overflow_2;
+    }
+
+    result
+}
+
+/// Add two reduced values; the modulus has 254 bits, so the sum does not overflow.
+fn fr_add(a: &[u64; 4], b: &[u64; 4]) -> [u64; 4] {
+    let mut result = [0u64; 4];
+    let mut carry = false;
+
+    for (i, limb) in result.iter_mut().enumerate() {
+        let (value, overflow_1) = a[i].overflowing_add(b[i]);
+        let (value, overflow_2) = value.overflowing_add(carry as u64);
+        *limb = value;
+        carry = overflow_1 || overflow_2;
+    }
+
+    fr_reduce(&result)
+}
+
+/// Montgomery multiplication: return `a * b * R^-1 mod FR_MODULUS`.
+fn fr_mul(a: &[u64; 4], b: &[u64; 4]) -> [u64; 4] {
+    let mut t = [0u64; 6];
+
+    for b_i in b {
+        let mut carry = 0u64;
+        for (t_j, a_j) in t.iter_mut().zip(a) {
+            let value = *t_j as u128 + (*a_j as u128) * (*b_i as u128) + carry as u128;
+            *t_j = value as u64;
+            carry = (value >> 64) as u64;
+        }
+        let value = t[4] as u128 + carry as u128;
+        t[4] = value as u64;
+        t[5] = (value >> 64) as u64;
+
+        let m = t[0].wrapping_mul(FR_INV);
+        let mut carry = ((t[0] as u128 + (m as u128) * (FR_MODULUS[0] as u128)) >> 64) as u64;
+        for j in 1..4 {
+            let value = t[j] as u128 + (m as u128) * (FR_MODULUS[j] as u128) + carry as u128;
+            t[j - 1] = value as u64;
+            carry = (value >> 64) as u64;
+        }
+        let value = t[4] as u128 + carry as u128;
+        t[3] = value as u64;
+        t[4] = t[5] + (value >> 64) as u64;
+    }
+
+    fr_reduce(&[t[0], t[1], t[2], t[3]])
+}
+
+fn fr_to_montgomery(a: &[u64; 4]) -> [u64; 4] {
+    fr_mul(a, &FR_R2)
+}
+
+fn fr_from_montgomery(a: &[u64; 4]) -> [u64; 4] {
+    fr_mul(a, &[1, 0, 0, 0])
+}
+
+fn fr_pow5(a: &[u64; 4]) -> [u64; 4] {
+    let square = fr_mul(a, a);
+    fr_mul(&fr_mul(&square, &square), a)
+}
+
+/// Return the inverse of a non-zero value (in Montgomery form) as `a^(p - 2)`.
+fn fr_inverse(a: &[u64; 4]) -> [u64; 4] {
+    let mut exponent = FR_MODULUS;
+    exponent[0] -= 2;
+
+    let mut result = fr_to_montgomery(&[1, 0, 0, 0]);
+
+    for i in (0..256).rev() {
+        result = fr_mul(&result, &result);
+
+        if (exponent[i / 64] >> (i % 64)) & 1 == 1 {
+            result = fr_mul(&result, a);
+        }
+    }
+
+    result
+}
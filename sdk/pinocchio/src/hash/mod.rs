@@ -0,0 +1,188 @@
+//! Hashing with the runtime's native hash functions.
+//!
+//! On-chain, the digests are computed by the runtime through syscalls, which is
+//! considerably cheaper than computing them within the program. Off-chain, the same
+//! digests are computed by a host implementation so that programs can be unit tested.
+//!
+//! All functions come in two flavours: a single slice version (e.g., [`sha256`]) and
+//! a multi-slice version (e.g., [`sha256v`]), which hashes the concatenation of the
+//! slices without requiring them to be copied into a single buffer.
+
+pub(crate) mod const_sha256;
+#[cfg(not(target_os = "solana"))]
+mod host;
+pub mod poseidon;
+
+/// Number of bytes in a hash.
+pub const HASH_BYTES: usize = 32;
+
+/// A hash digest.
+pub type Hash = [u8; HASH_BYTES];
+
+/// Return the SHA-256 hash of the given data.
+#[inline(always)]
+pub fn sha256(val: &[u8]) -> Hash {
+    sha256v(&[val])
+}
+
+/// Return the SHA-256 hash of the concatenation of the given slices.
+#[inline]
+pub fn sha256v(vals: &[&[u8]]) -> Hash {
+    #[cfg(target_os = "solana")]
+    {
+        let mut hash = [0u8; HASH_BYTES];
+        unsafe {
+            crate::syscalls::sol_sha256(
+                vals as *const _ as *const u8,
+                vals.len() as u64,
+                hash.as_mut_ptr(),
+            );
+        }
+        hash
+    }
+
+    #[cfg(not(target_os = "solana"))]
+    host::sha256(vals)
+}
+
+/// Return the Keccak-256 hash of the given data.
+///
+/// Note that this is the original Keccak padding used by Ethereum, which differs
+/// from the standardized SHA3-256.
+#[inline(always)]
+pub fn keccak256(val: &[u8]) -> Hash {
+    keccak256v(&[val])
+}
+
+/// Return the Keccak-256 hash of the concatenation of the given slices.
+#[inline]
+pub fn keccak256v(vals: &[&[u8]]) -> Hash {
+    #[cfg(target_os = "solana")]
+    {
+        let mut hash = [0u8; HASH_BYTES];
+        unsafe {
+            crate::syscalls::sol_keccak256(
+                vals as *const _ as *const u8,
+                vals.len() as u64,
+                hash.as_mut_ptr(),
+            );
+        }
+        hash
+    }
+
+    #[cfg(not(target_os = "solana"))]
+    host::keccak256(vals)
+}
+
+/// Return the BLAKE3 hash of the given data.
+#[inline(always)]
+pub fn blake3(val: &[u8]) -> Hash {
+    blake3v(&[val])
+}
+
+/// Return the BLAKE3 hash of the concatenation of the given slices.
+#[inline]
+pub fn blake3v(vals: &[&[u8]]) -> Hash {
+    #[cfg(target_os = "solana")]
+    {
+        let mut hash = [0u8; HASH_BYTES];
+        unsafe {
+            crate::syscalls::sol_blake3(
+                vals as *const _ as *const u8,
+                vals.len() as u64,
+                hash.as_mut_ptr(),
+            );
+        }
+        hash
+    }
+
+    #[cfg(not(target_os = "solana"))]
+    host::blake3(vals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Decode a hex string into a `Hash`.
+    fn hex(value: &str) -> Hash {
+        let mut hash = [0u8; HASH_BYTES];
+        for (i, byte) in hash.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&value[i * 2..i * 2 + 2], 16).unwrap();
+        }
+        hash
+    }
+
+    /// Input used by the BLAKE3 test vectors: a repeating sequence of `[0, 250]`.
+    fn blake3_input<const N: usize>() -> [u8; N] {
+        let mut input = [0u8; N];
+        for (i, byte) in input.iter_mut().enumerate() {
+            *byte = (i % 251) as u8;
+        }
+        input
+    }
+
+    #[test]
+    fn test_sha256() {
+        assert_eq!(
+            sha256(&[]),
+            hex("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855")
+        );
+        assert_eq!(
+            sha256(b"abc"),
+            hex("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+        assert_eq!(
+            sha256(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+            hex("248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1")
+        );
+    }
+
+    #[test]
+    fn test_keccak256() {
+        assert_eq!(
+            keccak256(&[]),
+            hex("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470")
+        );
+        assert_eq!(
+            keccak256(b"abc"),
+            hex("4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45")
+        );
+    }
+
+    #[test]
+    fn test_blake3() {
+        assert_eq!(
+            blake3(&[]),
+            hex("af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262")
+        );
+        assert_eq!(
+            blake3(&blake3_input::<1>()),
+            hex("2d3adedff11b61f14c886e35afa036736dcd87a74d27b5c1510225d0f592e213")
+        );
+        assert_eq!(
+            blake3(&blake3_input::<1024>()),
+            hex("42214739f095a406f3fc83deb889744ac00df831c10daa55189b5d121c855af7")
+        );
+        assert_eq!(
+            blake3(&blake3_input::<1025>()),
+            hex("d00278ae47eb27b34faecf67b4fe263f82d5412916c1ffd97c8cb7fb814b8444")
+        );
+        assert_eq!(
+            blake3(&blake3_input::<2048>()),
+            hex("e776b6028c7cd22a4d0ba182a8bf62205d2ef576467e838ed6f2529b85fba24a")
+        );
+    }
+
+    #[test]
+    fn test_hashv() {
+        let input = blake3_input::<4100>();
+        let (left, right) = input.split_at(1500);
+        let (middle, right) = right.split_at(700);
+
+        assert_eq!(sha256v(&[left, middle, right]), sha256(&input));
+        assert_eq!(keccak256v(&[left, middle, right]), keccak256(&input));
+        assert_eq!(blake3v(&[left, middle, right]), blake3(&input));
+        assert_eq!(sha256v(&[b"ab", &[], b"c"]), sha256(b"abc"));
+    }
+}
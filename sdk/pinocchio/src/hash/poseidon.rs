@@ -0,0 +1,301 @@
+//! Poseidon hash function over the BN254 scalar field.
+//!
+//! The hash is computed by the runtime using the Circom-compatible parameters, which
+//! means that the results match the ones produced by [`circomlib`] and [`light-poseidon`].
+//! Off-chain, the same parameters are derived by a host implementation.
+//!
+//! [`circomlib`]: https://github.com/iden3/circomlib
+//! [`light-poseidon`]: https://github.com/Lightprotocol/light-poseidon
+
+use super::{Hash, HASH_BYTES};
+use crate::program_error::ProgramError;
+
+/// Maximum number of inputs supported by the Poseidon syscall.
+pub const MAX_INPUTS: usize = 12;
+
+/// Parameters (curve and S-box) to use for the Poseidon hash.
+#[repr(u64)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Parameters {
+    /// BN254 curve with x^5 S-boxes (Circom-compatible).
+    Bn254X5 = 0,
+}
+
+/// Byte order of the inputs and output of the Poseidon hash.
+#[repr(u64)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Endianness {
+    /// Big-endian byte order.
+    BigEndian = 0,
+
+    /// Little-endian byte order.
+    LittleEndian = 1,
+}
+
+/// Errors that can be returned by the Poseidon syscall.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PoseidonError {
+    /// Invalid parameters value.
+    InvalidParameters,
+
+    /// Invalid endianness value.
+    InvalidEndianness,
+
+    /// The number of inputs must be between `1` and [`MAX_INPUTS`].
+    InvalidNumberOfInputs,
+
+    /// An input is empty.
+    EmptyInput,
+
+    /// An input is larger than [`HASH_BYTES`].
+    InvalidInputLength,
+
+    /// Failed to convert bytes into a prime field element.
+    BytesToPrimeFieldElement,
+
+    /// An input is larger than the field modulus.
+    InputLargerThanModulus,
+
+    /// Failed to convert a vector into an array.
+    VecToArray,
+
+    /// Failed to convert a `u64` into bytes.
+    U64Tou8,
+
+    /// Failed to convert bytes into a big integer.
+    BytesToBigInt,
+
+    /// Invalid width; the number of inputs plus one must be between `2` and `13`.
+    InvalidWidthCircom,
+
+    /// Unexpected error.
+    Unexpected,
+}
+
+impl From<u64> for PoseidonError {
+    fn from(error: u64) -> Self {
+        match error {
+            1 => PoseidonError::InvalidParameters,
+            2 => PoseidonError::InvalidEndianness,
+            3 => PoseidonError::InvalidNumberOfInputs,
+            4 => PoseidonError::EmptyInput,
+            5 => PoseidonError::InvalidInputLength,
+            6 => PoseidonError::BytesToPrimeFieldElement,
+            7 => PoseidonError::InputLargerThanModulus,
+            8 => PoseidonError::VecToArray,
+            9 => PoseidonError::U64Tou8,
+            10 => PoseidonError::BytesToBigInt,
+            11 => PoseidonError::InvalidWidthCircom,
+            _ => PoseidonError::Unexpected,
+        }
+    }
+}
+
+impl From<PoseidonError> for ProgramError {
+    fn from(_: PoseidonError) -> Self {
+        ProgramError::InvalidArgument
+    }
+}
+
+/// Return the Poseidon hash of the given inputs.
+///
+/// Each input is interpreted as a field element using the specified `endianness`,
+/// and must not be empty or larger than [`HASH_BYTES`].
+#[inline]
+pub fn hashv(
+    parameters: Parameters,
+    endianness: Endianness,
+    vals: &[&[u8]],
+) -> Result<Hash, PoseidonError> {
+    if vals.is_empty() || vals.len() > MAX_INPUTS {
+        return Err(PoseidonError::InvalidNumberOfInputs);
+    }
+
+    for val in vals {
+        if val.is_empty() {
+            return Err(PoseidonError::EmptyInput);
+        }
+        if val.len() > HASH_BYTES {
+            return Err(PoseidonError::InvalidInputLength);
+        }
+    }
+
+    #[cfg(target_os = "solana")]
+    {
+        let mut hash = [0u8; HASH_BYTES];
+
+        let result = unsafe {
+            crate::syscalls::sol_poseidon(
+                parameters as u64,
+                endianness as u64,
+                vals as *const _ as *const u8,
+                vals.len() as u64,
+                hash.as_mut_ptr(),
+            )
+        };
+
+        match result {
+            crate::SUCCESS => Ok(hash),
+            _ => Err(result.into()),
+        }
+    }
+
+    #[cfg(not(target_os = "solana"))]
+    match parameters {
+        Parameters::Bn254X5 => super::host::poseidon(endianness, vals),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invalid_inputs() {
+        assert_eq!(
+            hashv(Parameters::Bn254X5, Endianness::BigEndian, &[]),
+            Err(PoseidonError::InvalidNumberOfInputs)
+        );
+        assert_eq!(
+            hashv(
+                Parameters::Bn254X5,
+                Endianness::BigEndian,
+                &[&[1u8] as &[u8]; 13]
+            ),
+            Err(PoseidonError::InvalidNumberOfInputs)
+        );
+        assert_eq!(
+            hashv(Parameters::Bn254X5, Endianness::BigEndian, &[&[1], &[]]),
+            Err(PoseidonError::EmptyInput)
+        );
+        assert_eq!(
+            hashv(Parameters::Bn254X5, Endianness::LittleEndian, &[&[1; 33]]),
+            Err(PoseidonError::InvalidInputLength)
+        );
+    }
+
+    #[test]
+    fn test_circom_vectors() {
+        // created with circomlibjs `poseidon([1, ...])` for 1 to 12 inputs
+        const HASHES: [Hash; MAX_INPUTS] = [
+            [
+                41, 23, 97, 0, 234, 169, 98, 189, 193, 254, 108, 101, 77, 106, 60, 19, 14, 150,
+                164, 209, 22, 139, 51, 132, 139, 137, 125, 197, 2, 130, 1, 51,
+            ],
+            [
+                0, 122, 243, 70, 226, 211, 4, 39, 158, 121, 224, 169, 243, 2, 63, 119, 18, 148,
+                167, 138, 203, 112, 231, 63, 144, 175, 226, 124, 173, 64, 30, 129,
+            ],
+            [
+                2, 192, 6, 110, 16, 167, 42, 189, 43, 51, 195, 178, 20, 203, 62, 129, 188, 177,
+                182, 227, 9, 97, 205, 35, 194, 2, 177, 134, 115, 191, 37, 67,
+            ],
+            [
+                8, 44, 156, 55, 10, 13, 36, 244, 65, 111, 188, 65, 74, 55, 104, 31, 120, 68, 45,
+                39, 216, 99, 133, 153, 28, 23, 214, 252, 12, 75, 125, 113,
+            ],
+            [
+                16, 56, 150, 5, 174, 104, 141, 79, 20, 219, 133, 49, 34, 196, 125, 102, 168, 3,
+                199, 43, 65, 88, 156, 177, 191, 134, 135, 65, 178, 6, 185, 187,
+            ],
+            [
+                42, 115, 246, 121, 50, 140, 62, 171, 114, 74, 163, 229, 189, 191, 80, 179, 144, 53,
+                215, 114, 159, 19, 91, 151, 9, 137, 15, 133, 197, 220, 94, 118,
+            ],
+            [
+                34, 118, 49, 10, 167, 243, 52, 58, 40, 66, 20, 19, 157, 157, 169, 89, 190, 42, 49,
+                178, 199, 8, 165, 248, 25, 84, 178, 101, 229, 58, 48, 184,
+            ],
+            [
+                23, 126, 20, 83, 196, 70, 225, 176, 125, 43, 66, 51, 66, 81, 71, 9, 92, 79, 202,
+                187, 35, 61, 35, 11, 109, 70, 162, 20, 217, 91, 40, 132,
+            ],
+            [
+                14, 143, 238, 47, 228, 157, 163, 15, 222, 235, 72, 196, 46, 187, 68, 204, 110, 231,
+                5, 95, 97, 251, 202, 94, 49, 59, 138, 95, 202, 131, 76, 71,
+            ],
+            [
+                46, 196, 198, 94, 99, 120, 171, 140, 115, 48, 133, 79, 74, 112, 119, 193, 255, 146,
+                96, 228, 72, 133, 196, 184, 29, 209, 49, 173, 58, 134, 205, 150,
+            ],
+            [
+                0, 113, 61, 65, 236, 166, 53, 241, 23, 212, 236, 188, 235, 95, 58, 102, 220, 65,
+                66, 235, 112, 181, 103, 101, 188, 53, 143, 27, 236, 64, 187, 155,
+            ],
+            [
+                20, 57, 11, 224, 186, 239, 36, 155, 212, 124, 101, 221, 172, 101, 194, 229, 46,
+                133, 19, 192, 129, 193, 205, 114, 201, 128, 6, 9, 142, 154, 143, 190,
+            ],
+        ];
+
+        let mut one = [0u8; HASH_BYTES];
+        one[HASH_BYTES - 1] = 1;
+
+        for (i, expected) in HASHES.iter().enumerate() {
+            let inputs = [one.as_slice(); MAX_INPUTS];
+            assert_eq!(
+                hashv(Parameters::Bn254X5, Endianness::BigEndian, &inputs[..=i]),
+                Ok(*expected)
+            );
+            // shorter inputs are padded
+            assert_eq!(
+                hashv(
+                    Parameters::Bn254X5,
+                    Endianness::BigEndian,
+                    &[&[1u8] as &[u8]; MAX_INPUTS][..=i]
+                ),
+                Ok(*expected)
+            );
+        }
+    }
+
+    #[test]
+    fn test_endianness() {
+        let mut expected = [
+            13, 84, 225, 147, 143, 138, 140, 28, 125, 235, 94, 3, 85, 242, 99, 25, 32, 123, 132,
+            254, 156, 162, 206, 27, 38, 231, 53, 200, 41, 130, 25, 144,
+        ];
+        assert_eq!(
+            hashv(
+                Parameters::Bn254X5,
+                Endianness::BigEndian,
+                &[&[1; 32], &[2; 32]]
+            ),
+            Ok(expected)
+        );
+
+        expected.reverse();
+        assert_eq!(
+            hashv(
+                Parameters::Bn254X5,
+                Endianness::LittleEndian,
+                &[&[1; 32], &[2; 32]]
+            ),
+            Ok(expected)
+        );
+    }
+
+    #[test]
+    fn test_input_larger_than_modulus() {
+        assert_eq!(
+            hashv(Parameters::Bn254X5, Endianness::BigEndian, &[&[255; 32]]),
+            Err(PoseidonError::InputLargerThanModulus)
+        );
+        assert_eq!(
+            hashv(
+                Parameters::Bn254X5,
+                Endianness::LittleEndian,
+                &[&[1], &[255; 32]]
+            ),
+            Err(PoseidonError::InputLargerThanModulus)
+        );
+    }
+
+    #[test]
+    fn test_syscall_errors() {
+        assert_eq!(PoseidonError::from(1), PoseidonError::InvalidParameters);
+        assert_eq!(PoseidonError::from(11), PoseidonError::InvalidWidthCircom);
+        assert_eq!(PoseidonError::from(12), PoseidonError::Unexpected);
+        assert_eq!(PoseidonError::from(u64::MAX), PoseidonError::Unexpected);
+    }
+}
@@ -241,6 +241,14 @@ impl<'a, 'b, const SIZE: usize> From<&'b [Seed<'a>; SIZE]> for Signer<'a, 'b> {
     }
 }
 
+impl<'a> Deref for Signer<'a, '_> {
+    type Target = [Seed<'a>];
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { core::slice::from_raw_parts(self.seeds, self.len as usize) }
+    }
+}
+
 /// Convenience macro for constructing a `Signer` from a list of seeds
 /// represented as byte slices.
 ///
@@ -1,3 +1,5 @@
+use core::mem::MaybeUninit;
+
 use crate::{
     account_info::{Account, AccountInfo, MAX_PERMITTED_DATA_INCREASE},
     program_error::ProgramError,
@@ -29,6 +31,9 @@ use crate::{
 /// ) -> ProgramResult;
 /// ```
 ///
+/// The function can also take an [`InstructionContext`] with an account cache (e.g.,
+/// `InstructionContext<4>`), in which case the context is created with the cache.
+///
 /// # Examples
 ///
 /// Defining an entrypoint and making it conditional on the `bpf-entrypoint` feature. Although
@@ -63,7 +68,9 @@ macro_rules! lazy_entrypoint {
         /// Program entrypoint.
         #[no_mangle]
         pub unsafe extern "C" fn entrypoint(input: *mut u8) -> u64 {
-            match $process_instruction($crate::lazy_entrypoint::InstructionContext::new(input)) {
+            match $process_instruction($crate::lazy_entrypoint::InstructionContext::with_cache(
+                input,
+            )) {
                 Ok(_) => $crate::SUCCESS,
                 Err(error) => error.into(),
             }
@@ -75,7 +82,13 @@ macro_rules! lazy_entrypoint {
 ///
 /// This is a wrapper around the input buffer that provides methods to read the accounts
 /// and instruction data. It is used by the lazy entrypoint to access the input data on demand.
-pub struct InstructionContext {
+///
+/// The context can keep a cache of the first `CACHE` accounts read, which is used to
+/// resolve duplicated accounts in [`next_account_resolved`]. By default, there is no
+/// cache and reading an account does not incur any additional cost.
+///
+/// [`next_account_resolved`]: InstructionContext::next_account_resolved
+pub struct InstructionContext<const CACHE: usize = 0> {
     /// Pointer to the runtime input buffer for the instruction.
     input: *mut u8,
 
@@ -86,16 +99,39 @@ pub struct InstructionContext {
 
     /// Current memory offset on the input buffer.
     offset: usize,
+
+    /// Number of accounts read.
+    ///
+    /// This value is only tracked when the context has a cache.
+    position: usize,
+
+    /// The first `CACHE` accounts read, with duplicated accounts resolved.
+    ///
+    /// The accounts at indices `0..min(position, CACHE)` are initialized.
+    cache: [MaybeUninit<AccountInfo>; CACHE],
 }
 
 impl InstructionContext {
     /// Creates a new [`InstructionContext`] for the input buffer.
     #[inline(always)]
     pub fn new(input: *mut u8) -> Self {
+        Self::with_cache(input)
+    }
+}
+
+impl<const CACHE: usize> InstructionContext<CACHE> {
+    /// Creates a new [`InstructionContext`] for the input buffer that caches the first
+    /// `CACHE` accounts read.
+    #[inline(always)]
+    pub fn with_cache(input: *mut u8) -> Self {
+        const UNINIT: MaybeUninit<AccountInfo> = MaybeUninit::<AccountInfo>::uninit();
+
         Self {
             input,
             remaining: unsafe { *(input as *const u64) },
             offset: core::mem::size_of::<u64>(),
+            position: 0,
+            cache: [UNINIT; CACHE],
         }
     }
 
@@ -116,7 +152,78 @@ impl InstructionContext {
             .checked_sub(1)
             .ok_or(ProgramError::NotEnoughAccountKeys)?;
 
-        Ok(unsafe { read_account(self.input, &mut self.offset) })
+        Ok(unsafe { self.read_account() })
+    }
+
+    /// Reads the next account for the instruction, resolving duplicated accounts.
+    ///
+    /// Duplicated accounts are resolved using the account cache, so the original
+    /// account must be one of the first `CACHE` accounts.
+    ///
+    /// # Error
+    ///
+    /// Returns a [`ProgramError::NotEnoughAccountKeys`] error if there are no remaining
+    /// accounts and a [`ProgramError::InvalidArgument`] error if the account is a
+    /// duplicate of an account that is not in the cache.
+    #[inline(always)]
+    pub fn next_account_resolved(&mut self) -> Result<AccountInfo, ProgramError> {
+        match self.next_account()? {
+            MaybeAccount::Account(account) => Ok(account),
+            MaybeAccount::Duplicated(index) => self
+                .cached_account(index as usize)
+                .cloned()
+                .ok_or(ProgramError::InvalidArgument),
+        }
+    }
+
+    /// Returns the account at the given index if it is in the cache.
+    ///
+    /// Only the first `CACHE` accounts are cached, and only once they have been read.
+    #[inline(always)]
+    pub fn cached_account(&self, index: usize) -> Option<&AccountInfo> {
+        if index < core::cmp::min(self.position, CACHE) {
+            // SAFETY: the first `min(position, CACHE)` accounts are initialized.
+            Some(unsafe { self.cache[index].assume_init_ref() })
+        } else {
+            None
+        }
+    }
+
+    /// Skips the next `count` accounts.
+    ///
+    /// Skipped accounts are only parsed to move the offset forward, unless they
+    /// need to be cached.
+    ///
+    /// # Error
+    ///
+    /// Returns a [`ProgramError::NotEnoughAccountKeys`] error if there are fewer than
+    /// `count` remaining accounts; in this case, no account is skipped.
+    #[inline(always)]
+    pub fn skip_accounts(&mut self, count: u64) -> Result<(), ProgramError> {
+        self.remaining = self
+            .remaining
+            .checked_sub(count)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+        for _ in 0..count {
+            if self.position < CACHE {
+                unsafe { self.read_account() };
+            } else {
+                unsafe { skip_account(self.input, &mut self.offset) };
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Skips all remaining accounts and returns the instruction data for the
+    /// instruction.
+    #[inline(always)]
+    pub fn skip_to_instruction_data(&mut self) -> (&[u8], &Pubkey) {
+        // there are always enough remaining accounts
+        let _ = self.skip_accounts(self.remaining);
+        // SAFETY: all accounts have been read.
+        unsafe { self.instruction_data_unchecked() }
     }
 
     /// Returns the next account for the instruction.
@@ -131,7 +238,31 @@ impl InstructionContext {
     /// there are no more remaining accounts results in undefined behavior.
     #[inline(always)]
     pub unsafe fn next_account_unchecked(&mut self) -> MaybeAccount {
-        read_account(self.input, &mut self.offset)
+        self.read_account()
+    }
+
+    /// Reads the next account from the input buffer and updates the cache.
+    #[inline(always)]
+    unsafe fn read_account(&mut self) -> MaybeAccount {
+        let account = read_account(self.input, &mut self.offset);
+
+        if CACHE > 0 {
+            if self.position < CACHE {
+                let cached = match &account {
+                    MaybeAccount::Account(account) => account.clone(),
+                    // duplicates always refer to a previous account, which is
+                    // in the cache
+                    MaybeAccount::Duplicated(index) => {
+                        self.cache[*index as usize].assume_init_ref().clone()
+                    }
+                };
+                self.cache[self.position].write(cached);
+            }
+
+            self.position += 1;
+        }
+
+        account
     }
 
     /// Returns the number of available accounts.
@@ -202,13 +333,33 @@ impl MaybeAccount {
     }
 }
 
+/// Move the offset past an account in the input buffer, without reading it.
+///
+/// This can only be called with a buffer that was serialized by the runtime as
+/// it assumes a specific memory layout.
+#[allow(clippy::cast_ptr_alignment, clippy::missing_safety_doc)]
+#[inline(always)]
+unsafe fn skip_account(input: *mut u8, offset: &mut usize) {
+    let account: *mut Account = input.add(*offset) as *mut _;
+
+    if (*account).borrow_state == NON_DUP_MARKER {
+        *offset += core::mem::size_of::<Account>();
+        *offset += (*account).data_len as usize;
+        *offset += MAX_PERMITTED_DATA_INCREASE;
+        *offset += (*offset as *const u8).align_offset(BPF_ALIGN_OF_U128);
+        *offset += core::mem::size_of::<u64>();
+    } else {
+        *offset += core::mem::size_of::<u64>();
+    }
+}
+
 /// Read an account from the input buffer.
 ///
 /// This can only be called with a buffer that was serialized by the runtime as
 /// it assumes a specific memory layout.
 #[allow(clippy::cast_ptr_alignment, clippy::missing_safety_doc)]
 #[inline(always)]
-unsafe fn read_account(input: *mut u8, offset: &mut usize) -> MaybeAccount {
+pub(crate) unsafe fn read_account(input: *mut u8, offset: &mut usize) -> MaybeAccount {
     let account: *mut Account = input.add(*offset) as *mut _;
 
     if (*account).borrow_state == NON_DUP_MARKER {
@@ -11,8 +11,16 @@
 #![no_std]
 #![cfg_attr(target_os = "solana", feature(asm_experimental_arch, asm_const))]
 
+#[cfg(feature = "std")]
+extern crate std;
+
 pub mod account_info;
+pub mod big_mod_exp;
+pub mod coption;
+pub mod curves;
+pub mod discriminator;
 pub mod entrypoint;
+pub mod hash;
 pub mod instruction;
 pub mod lazy_entrypoint;
 pub mod log;
@@ -20,8 +28,11 @@ pub mod memory;
 pub mod program;
 pub mod program_error;
 pub mod pubkey;
+pub mod secp256k1_recover;
 pub mod syscalls;
 pub mod sysvars;
+#[cfg(all(feature = "std", not(target_os = "solana")))]
+pub mod testing;
 
 /// Maximum number of accounts that a transaction may process.
 ///
@@ -33,6 +33,7 @@
 //! [`Pubkey`]: crate::pubkey::Pubkey
 //! [`Pubkey::log`]: crate::pubkey::Pubkey::log
 
+#[cfg(feature = "std")]
 use crate::{account_info::AccountInfo, pubkey::log};
 
 #[macro_export]
@@ -60,7 +61,10 @@ pub fn sol_log(message: &str) {
         crate::syscalls::sol_log_(message.as_ptr(), message.len() as u64);
     }
 
-    #[cfg(not(target_os = "solana"))]
+    #[cfg(all(not(target_os = "solana"), feature = "std"))]
+    crate::testing::with_syscall_stubs(|stubs| stubs.sol_log(message));
+
+    #[cfg(all(not(target_os = "solana"), not(feature = "std")))]
     core::hint::black_box(message);
 }
 
@@ -72,7 +76,10 @@ pub fn sol_log_64(arg1: u64, arg2: u64, arg3: u64, arg4: u64, arg5: u64) {
         crate::syscalls::sol_log_64_(arg1, arg2, arg3, arg4, arg5);
     }
 
-    #[cfg(not(target_os = "solana"))]
+    #[cfg(all(not(target_os = "solana"), feature = "std"))]
+    crate::testing::with_syscall_stubs(|stubs| stubs.sol_log_64(arg1, arg2, arg3, arg4, arg5));
+
+    #[cfg(all(not(target_os = "solana"), not(feature = "std")))]
     core::hint::black_box((arg1, arg2, arg3, arg4, arg5));
 }
 
@@ -83,7 +90,10 @@ pub fn sol_log_data(data: &[&[u8]]) {
         crate::syscalls::sol_log_data(data as *const _ as *const u8, data.len() as u64)
     };
 
-    #[cfg(not(target_os = "solana"))]
+    #[cfg(all(not(target_os = "solana"), feature = "std"))]
+    crate::testing::with_syscall_stubs(|stubs| stubs.sol_log_data(data));
+
+    #[cfg(all(not(target_os = "solana"), not(feature = "std")))]
     core::hint::black_box(data);
 }
 
@@ -125,4 +135,7 @@ pub fn sol_log_compute_units() {
     unsafe {
         crate::syscalls::sol_log_compute_units_();
     }
+
+    #[cfg(all(not(target_os = "solana"), feature = "std"))]
+    crate::testing::with_syscall_stubs(|stubs| stubs.sol_log_compute_units());
 }
@@ -19,6 +19,7 @@ use crate::{
 /// discarded immediately after.
 #[repr(C)]
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(not(target_os = "solana"), allow(dead_code))]
 struct CInstruction<'a> {
     /// Public key of the program.
     program_id: *const Pubkey,
@@ -104,10 +105,8 @@ pub fn invoke_signed<const ACCOUNTS: usize>(
             instruction,
             core::slice::from_raw_parts(accounts.as_ptr() as _, ACCOUNTS),
             signers_seeds,
-        );
+        )
     }
-
-    Ok(())
 }
 
 /// Invoke a cross-program instruction but don't enforce Rust's aliasing rules.
@@ -127,8 +126,12 @@ pub fn invoke_signed<const ACCOUNTS: usize>(
 ///
 /// The accounts on the `account_infos` slice must be in the same order as the
 /// `accounts` field of the `instruction`.
+///
+/// # Errors
+///
+/// Returns the error reported by the runtime when the invoked program fails.
 #[inline(always)]
-pub unsafe fn invoke_unchecked(instruction: &Instruction, accounts: &[Account]) {
+pub unsafe fn invoke_unchecked(instruction: &Instruction, accounts: &[Account]) -> ProgramResult {
     invoke_signed_unchecked(instruction, accounts, &[])
 }
 
@@ -150,15 +153,20 @@ pub unsafe fn invoke_unchecked(instruction: &Instruction, accounts: &[Account])
 ///
 /// The accounts on the `account_infos` slice must be in the same order as the
 /// `accounts` field of the `instruction`.
+///
+/// # Errors
+///
+/// Returns the error reported by the runtime when the invoked program fails. The
+/// value returned by the syscall is mapped using [`ProgramError::from`].
 pub unsafe fn invoke_signed_unchecked(
     instruction: &Instruction,
     accounts: &[Account],
     signers_seeds: &[Signer],
-) {
+) -> ProgramResult {
     #[cfg(target_os = "solana")]
     {
         let instruction = CInstruction::from(instruction);
-        unsafe {
+        let result = unsafe {
             crate::syscalls::sol_invoke_signed_c(
                 &instruction as *const _ as *const u8,
                 accounts as *const _ as *const u8,
@@ -167,10 +175,25 @@ pub unsafe fn invoke_signed_unchecked(
                 signers_seeds.len() as u64,
             )
         };
+
+        match result {
+            crate::SUCCESS => Ok(()),
+            _ => Err(result.into()),
+        }
     }
 
-    #[cfg(not(target_os = "solana"))]
-    core::hint::black_box((instruction, accounts, signers_seeds));
+    #[cfg(all(not(target_os = "solana"), feature = "std"))]
+    {
+        crate::testing::with_syscall_stubs(|stubs| {
+            stubs.sol_invoke_signed(instruction, accounts, signers_seeds)
+        })
+    }
+
+    #[cfg(all(not(target_os = "solana"), not(feature = "std")))]
+    {
+        core::hint::black_box((instruction, accounts, signers_seeds));
+        Ok(())
+    }
 }
 
 /// Maximum size that can be set using [`set_return_data`].
@@ -189,7 +212,10 @@ pub fn set_return_data(data: &[u8]) {
         crate::syscalls::sol_set_return_data(data.as_ptr(), data.len() as u64)
     };
 
-    #[cfg(not(target_os = "solana"))]
+    #[cfg(all(not(target_os = "solana"), feature = "std"))]
+    crate::testing::with_syscall_stubs(|stubs| stubs.sol_set_return_data(data));
+
+    #[cfg(all(not(target_os = "solana"), not(feature = "std")))]
     core::hint::black_box(data);
 }
 
@@ -247,7 +273,24 @@ pub fn get_return_data() -> Option<ReturnData> {
         }
     }
 
-    #[cfg(not(target_os = "solana"))]
+    #[cfg(all(not(target_os = "solana"), feature = "std"))]
+    {
+        crate::testing::with_syscall_stubs(|stubs| stubs.sol_get_return_data()).map(
+            |(program_id, return_data)| {
+                let size = core::cmp::min(return_data.len(), MAX_RETURN_DATA);
+                let mut data = [0u8; MAX_RETURN_DATA];
+                data[..size].copy_from_slice(&return_data[..size]);
+
+                ReturnData {
+                    program_id,
+                    data,
+                    size,
+                }
+            },
+        )
+    }
+
+    #[cfg(all(not(target_os = "solana"), not(feature = "std")))]
     core::hint::black_box(None)
 }
 
@@ -24,7 +24,10 @@ pub fn log(pubkey: &Pubkey) {
         crate::syscalls::sol_log_pubkey(pubkey as *const _ as *const u8)
     };
 
-    #[cfg(not(target_os = "solana"))]
+    #[cfg(all(not(target_os = "solana"), feature = "std"))]
+    crate::testing::with_syscall_stubs(|stubs| stubs.sol_log_pubkey(pubkey));
+
+    #[cfg(all(not(target_os = "solana"), not(feature = "std")))]
     core::hint::black_box(pubkey);
 }
 
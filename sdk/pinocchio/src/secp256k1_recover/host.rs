@@ -0,0 +1,373 @@
+//! Host implementation of the secp256k1 public key recovery.
+//!
+//! This is a straightforward (non-optimized and not constant-time) implementation
+//! used when the program is not running on the SBF target. It must not be used to
+//! handle secret values.
+
+use super::{Secp256k1RecoverError, SECP256K1_PUBLIC_KEY_LENGTH, SECP256K1_SIGNATURE_LENGTH};
+use crate::hash::Hash;
+
+/// A 256-bit unsigned integer represented as little-endian `u64` limbs.
+type U256 = [u64; 4];
+
+/// The prime modulus of the secp256k1 base field.
+const P: U256 = [
+    0xFFFFFFFEFFFFFC2F,
+    0xFFFFFFFFFFFFFFFF,
+    0xFFFFFFFFFFFFFFFF,
+    0xFFFFFFFFFFFFFFFF,
+];
+
+/// The order of the secp256k1 group.
+const N: U256 = [
+    0xBFD25E8CD0364141,
+    0xBAAEDCE6AF48A03B,
+    0xFFFFFFFFFFFFFFFE,
+    0xFFFFFFFFFFFFFFFF,
+];
+
+/// The `x` coordinate of the secp256k1 generator point.
+const GX: U256 = [
+    0x59F2815B16F81798,
+    0x029BFCDB2DCE28D9,
+    0x55A06295CE870B07,
+    0x79BE667EF9DCBBAC,
+];
+
+/// The `y` coordinate of the secp256k1 generator point.
+const GY: U256 = [
+    0x9C47D08FFB10D4B8,
+    0xFD17B448A6855419,
+    0x5DA4FBFC0E1108A8,
+    0x483ADA7726A3C465,
+];
+
+const ZERO: U256 = [0; 4];
+
+const ONE: U256 = [1, 0, 0, 0];
+
+/// Recover the public key from a signature.
+pub fn recover(
+    hash: &Hash,
+    recovery_id: u8,
+    signature: &[u8; SECP256K1_SIGNATURE_LENGTH],
+) -> Result<[u8; SECP256K1_PUBLIC_KEY_LENGTH], Secp256k1RecoverError> {
+    if recovery_id > 3 {
+        return Err(Secp256k1RecoverError::InvalidRecoveryId);
+    }
+
+    let r = from_be_bytes(&signature[..32]);
+    let s = from_be_bytes(&signature[32..]);
+
+    if is_zero(&r) || is_zero(&s) || !less_than(&r, &N) || !less_than(&s, &N) {
+        return Err(Secp256k1RecoverError::InvalidSignature);
+    }
+
+    // x coordinate of the point R: the second bit of the recovery id indicates
+    // that the value overflowed the group order
+    let x = if recovery_id & 2 != 0 {
+        let (x, carry) = add(&r, &N);
+        if carry || !less_than(&x, &P) {
+            return Err(Secp256k1RecoverError::InvalidSignature);
+        }
+        x
+    } else {
+        r
+    };
+
+    // y coordinate of the point R: y^2 = x^3 + 7; since p = 3 mod 4, the square
+    // root is y = (x^3 + 7)^((p + 1) / 4)
+    let y_squared = add_mod(&mul_mod(&mul_mod(&x, &x, &P), &x, &P), &[7, 0, 0, 0], &P);
+    let mut y = pow_mod(&y_squared, &p_plus_one_div_four(), &P);
+
+    if mul_mod(&y, &y, &P) != y_squared {
+        return Err(Secp256k1RecoverError::InvalidSignature);
+    }
+    // the first bit of the recovery id indicates the parity of y
+    if (y[0] & 1) as u8 != recovery_id & 1 {
+        y = sub_mod(&ZERO, &y, &P);
+    }
+
+    // message hash as a scalar
+    let mut e = from_be_bytes(hash);
+    if !less_than(&e, &N) {
+        e = sub(&e, &N).0;
+    }
+
+    // Q = r^-1 (sR - eG)
+    let r_inverse = pow_mod(&r, &sub(&N, &[2, 0, 0, 0]).0, &N);
+    let u1 = mul_mod(&sub_mod(&ZERO, &e, &N), &r_inverse, &N);
+    let u2 = mul_mod(&s, &r_inverse, &N);
+
+    let q = JacobianPoint::from_affine(&GX, &GY)
+        .mul(&u1)
+        .add(&JacobianPoint::from_affine(&x, &y).mul(&u2));
+
+    let (qx, qy) = q
+        .to_affine()
+        .ok_or(Secp256k1RecoverError::InvalidSignature)?;
+
+    let mut pubkey = [0u8; SECP256K1_PUBLIC_KEY_LENGTH];
+    pubkey[..32].copy_from_slice(&to_be_bytes(&qx));
+    pubkey[32..].copy_from_slice(&to_be_bytes(&qy));
+
+    Ok(pubkey)
+}
+
+/// A point on the curve in Jacobian coordinates, where `(X, Y, Z)` represents the
+/// affine point `(X / Z^2, Y / Z^3)`. The point at infinity has `Z = 0`.
+#[derive(Clone, Copy)]
+struct JacobianPoint {
+    x: U256,
+    y: U256,
+    z: U256,
+}
+
+impl JacobianPoint {
+    const INFINITY: JacobianPoint = JacobianPoint {
+        x: ONE,
+        y: ONE,
+        z: ZERO,
+    };
+
+    fn from_affine(x: &U256, y: &U256) -> Self {
+        Self {
+            x: *x,
+            y: *y,
+            z: ONE,
+        }
+    }
+
+    fn is_infinity(&self) -> bool {
+        is_zero(&self.z)
+    }
+
+    fn to_affine(self) -> Option<(U256, U256)> {
+        if self.is_infinity() {
+            return None;
+        }
+
+        let z_inverse = pow_mod(&self.z, &sub(&P, &[2, 0, 0, 0]).0, &P);
+        let z_inverse_2 = mul_mod(&z_inverse, &z_inverse, &P);
+        let z_inverse_3 = mul_mod(&z_inverse_2, &z_inverse, &P);
+
+        Some((
+            mul_mod(&self.x, &z_inverse_2, &P),
+            mul_mod(&self.y, &z_inverse_3, &P),
+        ))
+    }
+
+    fn double(&self) -> Self {
+        if self.is_infinity() || is_zero(&self.y) {
+            return Self::INFINITY;
+        }
+
+        let a = mul_mod(&self.x, &self.x, &P);
+        let b = mul_mod(&self.y, &self.y, &P);
+        let c = mul_mod(&b, &b, &P);
+        // d = 2 * ((x + b)^2 - a - c)
+        let x_plus_b = add_mod(&self.x, &b, &P);
+        let d = sub_mod(&sub_mod(&mul_mod(&x_plus_b, &x_plus_b, &P), &a, &P), &c, &P);
+        let d = add_mod(&d, &d, &P);
+        // e = 3 * a
+        let e = add_mod(&add_mod(&a, &a, &P), &a, &P);
+        let f = mul_mod(&e, &e, &P);
+
+        let x = sub_mod(&f, &add_mod(&d, &d, &P), &P);
+        // y = e * (d - x) - 8 * c
+        let c_8 = add_mod(&c, &c, &P);
+        let c_8 = add_mod(&c_8, &c_8, &P);
+        let c_8 = add_mod(&c_8, &c_8, &P);
+        let y = sub_mod(&mul_mod(&e, &sub_mod(&d, &x, &P), &P), &c_8, &P);
+        // z = 2 * y * z
+        let z = mul_mod(&self.y, &self.z, &P);
+        let z = add_mod(&z, &z, &P);
+
+        Self { x, y, z }
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        if self.is_infinity() {
+            return *other;
+        }
+        if other.is_infinity() {
+            return *self;
+        }
+
+        let z1_z1 = mul_mod(&self.z, &self.z, &P);
+        let z2_z2 = mul_mod(&other.z, &other.z, &P);
+        let u1 = mul_mod(&self.x, &z2_z2, &P);
+        let u2 = mul_mod(&other.x, &z1_z1, &P);
+        let s1 = mul_mod(&mul_mod(&self.y, &other.z, &P), &z2_z2, &P);
+        let s2 = mul_mod(&mul_mod(&other.y, &self.z, &P), &z1_z1, &P);
+
+        let h = sub_mod(&u2, &u1, &P);
+        let r = sub_mod(&s2, &s1, &P);
+
+        if is_zero(&h) {
+            return if is_zero(&r) {
+                self.double()
+            } else {
+                Self::INFINITY
+            };
+        }
+
+        let h_h = mul_mod(&h, &h, &P);
+        let h_h_h = mul_mod(&h, &h_h, &P);
+        let v = mul_mod(&u1, &h_h, &P);
+
+        // x = r^2 - h^3 - 2 * v
+        let x = sub_mod(
+            &sub_mod(&mul_mod(&r, &r, &P), &h_h_h, &P),
+            &add_mod(&v, &v, &P),
+            &P,
+        );
+        // y = r * (v - x) - s1 * h^3
+        let y = sub_mod(
+            &mul_mod(&r, &sub_mod(&v, &x, &P), &P),
+            &mul_mod(&s1, &h_h_h, &P),
+            &P,
+        );
+        // z = z1 * z2 * h
+        let z = mul_mod(&mul_mod(&self.z, &other.z, &P), &h, &P);
+
+        Self { x, y, z }
+    }
+
+    fn mul(&self, scalar: &U256) -> Self {
+        let mut result = Self::INFINITY;
+
+        for i in (0..256).rev() {
+            result = result.double();
+            if bit(scalar, i) {
+                result = result.add(self);
+            }
+        }
+
+        result
+    }
+}
+
+/// Return `(p + 1) / 4`, the exponent used to compute square roots.
+fn p_plus_one_div_four() -> U256 {
+    let (value, _) = add(&P, &ONE);
+    [
+        (value[0] >> 2) | (value[1] << 62),
+        (value[1] >> 2) | (value[2] << 62),
+        (value[2] >> 2) | (value[3] << 62),
+        value[3] >> 2,
+    ]
+}
+
+fn from_be_bytes(bytes: &[u8]) -> U256 {
+    let mut value = ZERO;
+    for (i, chunk) in bytes.chunks_exact(8).enumerate() {
+        value[3 - i] = u64::from_be_bytes(chunk.try_into().unwrap());
+    }
+    value
+}
+
+fn to_be_bytes(value: &U256) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    for (i, chunk) in bytes.chunks_exact_mut(8).enumerate() {
+        chunk.copy_from_slice(&value[3 - i].to_be_bytes());
+    }
+    bytes
+}
+
+#[inline(always)]
+fn is_zero(value: &U256) -> bool {
+    value.iter().all(|limb| *limb == 0)
+}
+
+#[inline(always)]
+fn bit(value: &U256, index: usize) -> bool {
+    (value[index / 64] >> (index % 64)) & 1 == 1
+}
+
+fn less_than(a: &U256, b: &U256) -> bool {
+    for i in (0..4).rev() {
+        if a[i] != b[i] {
+            return a[i] < b[i];
+        }
+    }
+    false
+}
+
+fn add(a: &U256, b: &U256) -> (U256, bool) {
+    let mut result = ZERO;
+    let mut carry = false;
+
+    for i in 0..4 {
+        let (sum, c1) = a[i].overflowing_add(b[i]);
+        let (sum, c2) = sum.overflowing_add(carry as u64);
+        result[i] = sum;
+        carry = c1 || c2;
+    }
+
+    (result, carry)
+}
+
+fn sub(a: &U256, b: &U256) -> (U256, bool) {
+    let mut result = ZERO;
+    let mut borrow = false;
+
+    for i in 0..4 {
+        let (difference, b1) = a[i].overflowing_sub(b[i]);
+        let (difference, b2) = difference.overflowing_sub(borrow as u64);
+        result[i] = difference;
+        borrow = b1 || b2;
+    }
+
+    (result, borrow)
+}
+
+/// Return `(a + b) mod m`, assuming `a, b < m`.
+fn add_mod(a: &U256, b: &U256, m: &U256) -> U256 {
+    let (sum, carry) = add(a, b);
+
+    if carry || !less_than(&sum, m) {
+        sub(&sum, m).0
+    } else {
+        sum
+    }
+}
+
+/// Return `(a - b) mod m`, assuming `a, b < m`.
+fn sub_mod(a: &U256, b: &U256, m: &U256) -> U256 {
+    let (difference, borrow) = sub(a, b);
+
+    if borrow {
+        add(&difference, m).0
+    } else {
+        difference
+    }
+}
+
+/// Return `(a * b) mod m`, assuming `a, b < m`.
+fn mul_mod(a: &U256, b: &U256, m: &U256) -> U256 {
+    let mut result = ZERO;
+
+    for i in (0..256).rev() {
+        result = add_mod(&result, &result, m);
+        if bit(b, i) {
+            result = add_mod(&result, a, m);
+        }
+    }
+
+    result
+}
+
+/// Return `(base ^ exponent) mod m`, assuming `base < m`.
+fn pow_mod(base: &U256, exponent: &U256, m: &U256) -> U256 {
+    let mut result = ONE;
+
+    for i in (0..256).rev() {
+        result = mul_mod(&result, &result, m);
+        if bit(exponent, i) {
+            result = mul_mod(&result, base, m);
+        }
+    }
+
+    result
+}
@@ -0,0 +1,266 @@
+//! Public key recovery from [secp256k1] ECDSA signatures.
+//!
+//! [secp256k1]: https://en.bitcoin.it/wiki/Secp256k1
+//!
+//! The recovery is performed by the runtime through the `sol_secp256k1_recover`
+//! syscall, which is typically used to verify Ethereum signatures: the recovered
+//! public key can be hashed with [`keccak256`] to obtain the signer's Ethereum
+//! address. Off-chain, the same recovery is performed by a host implementation so
+//! that programs can be unit tested.
+//!
+//! Note that the syscall does not reject signatures with a "high" `S` value, so
+//! programs that require non-malleable signatures must check the `S` value
+//! themselves.
+//!
+//! [`keccak256`]: crate::hash::keccak256
+
+#[cfg(not(target_os = "solana"))]
+mod host;
+
+use crate::{hash::Hash, program_error::ProgramError};
+
+/// Length of a secp256k1 signature (`r` and `s` values).
+pub const SECP256K1_SIGNATURE_LENGTH: usize = 64;
+
+/// Length of an uncompressed secp256k1 public key (without the `0x04` prefix).
+pub const SECP256K1_PUBLIC_KEY_LENGTH: usize = 64;
+
+/// A recovered secp256k1 public key.
+///
+/// The key is represented by the big-endian encoding of its `x` and `y` coordinates,
+/// which is the uncompressed SEC1 encoding without the `0x04` prefix.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Secp256k1Pubkey(pub [u8; SECP256K1_PUBLIC_KEY_LENGTH]);
+
+impl Secp256k1Pubkey {
+    /// Create a new `Secp256k1Pubkey` from its byte representation.
+    #[inline(always)]
+    pub const fn new(bytes: [u8; SECP256K1_PUBLIC_KEY_LENGTH]) -> Self {
+        Self(bytes)
+    }
+
+    /// Return the byte representation of the public key.
+    #[inline(always)]
+    pub const fn to_bytes(self) -> [u8; SECP256K1_PUBLIC_KEY_LENGTH] {
+        self.0
+    }
+
+    /// Return a reference to the byte representation of the public key.
+    #[inline(always)]
+    pub const fn as_bytes(&self) -> &[u8; SECP256K1_PUBLIC_KEY_LENGTH] {
+        &self.0
+    }
+}
+
+/// The recovery id of a signature.
+///
+/// The recovery id is a value in the range `[0, 3]` that identifies which of the
+/// possible public keys was used to create the signature.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RecoveryId(u8);
+
+impl RecoveryId {
+    /// Create a new `RecoveryId`, returning `None` if the value is not in the
+    /// range `[0, 3]`.
+    #[inline(always)]
+    pub const fn new(value: u8) -> Option<Self> {
+        if value <= 3 {
+            Some(Self(value))
+        } else {
+            None
+        }
+    }
+
+    /// Return the value of the recovery id.
+    #[inline(always)]
+    pub const fn value(self) -> u8 {
+        self.0
+    }
+}
+
+impl TryFrom<u8> for RecoveryId {
+    type Error = Secp256k1RecoverError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Self::new(value).ok_or(Secp256k1RecoverError::InvalidRecoveryId)
+    }
+}
+
+/// Errors that can be returned by the `sol_secp256k1_recover` syscall.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Secp256k1RecoverError {
+    /// The message hash is invalid.
+    InvalidHash,
+
+    /// The recovery id is invalid.
+    InvalidRecoveryId,
+
+    /// The signature is invalid or does not correspond to any public key.
+    InvalidSignature,
+
+    /// Error code not known by this crate.
+    Unexpected(u64),
+}
+
+impl From<u64> for Secp256k1RecoverError {
+    fn from(error: u64) -> Self {
+        match error {
+            1 => Secp256k1RecoverError::InvalidHash,
+            2 => Secp256k1RecoverError::InvalidRecoveryId,
+            3 => Secp256k1RecoverError::InvalidSignature,
+            _ => Secp256k1RecoverError::Unexpected(error),
+        }
+    }
+}
+
+impl From<Secp256k1RecoverError> for u64 {
+    fn from(error: Secp256k1RecoverError) -> Self {
+        match error {
+            Secp256k1RecoverError::InvalidHash => 1,
+            Secp256k1RecoverError::InvalidRecoveryId => 2,
+            Secp256k1RecoverError::InvalidSignature => 3,
+            Secp256k1RecoverError::Unexpected(error) => error,
+        }
+    }
+}
+
+impl From<Secp256k1RecoverError> for ProgramError {
+    fn from(_: Secp256k1RecoverError) -> Self {
+        ProgramError::InvalidArgument
+    }
+}
+
+/// Recover the public key that created the `signature` over the message `hash`.
+///
+/// The `signature` is the big-endian encoding of the `r` and `s` values. Ethereum
+/// signatures carry the recovery id in their `v` value, which is usually `27 + recovery_id`.
+///
+/// # Errors
+///
+/// Returns [`Secp256k1RecoverError::InvalidSignature`] if the `r` or `s` values are not
+/// valid scalars or if there is no public key that matches the signature.
+#[inline]
+pub fn secp256k1_recover(
+    hash: &Hash,
+    recovery_id: RecoveryId,
+    signature: &[u8; SECP256K1_SIGNATURE_LENGTH],
+) -> Result<Secp256k1Pubkey, Secp256k1RecoverError> {
+    #[cfg(target_os = "solana")]
+    {
+        let mut pubkey = [0u8; SECP256K1_PUBLIC_KEY_LENGTH];
+
+        let result = unsafe {
+            crate::syscalls::sol_secp256k1_recover(
+                hash.as_ptr(),
+                recovery_id.value() as u64,
+                signature.as_ptr(),
+                pubkey.as_mut_ptr(),
+            )
+        };
+
+        match result {
+            crate::SUCCESS => Ok(Secp256k1Pubkey(pubkey)),
+            _ => Err(result.into()),
+        }
+    }
+
+    #[cfg(not(target_os = "solana"))]
+    host::recover(hash, recovery_id.value(), signature).map(Secp256k1Pubkey)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::sha256;
+
+    /// Decode a hex string into a byte array.
+    fn hex<const N: usize>(value: &str) -> [u8; N] {
+        let mut bytes = [0u8; N];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&value[i * 2..i * 2 + 2], 16).unwrap();
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_recover() {
+        let signature = hex("469b3b36048954f0e9293ebad9a6aae532b23b27a3b40793e77c9184f64c6da2ce32d791e53cc1b90917e14bf55ef33ce82aa53c089b4fb4a54a05fd717e000a");
+        let expected = hex("83d6da749d96bffd62a663c2239eea31c305be5402ff4bf252aa061e64deaa758ff1afad06cd8e2ca37b672869aaae06b40f7a24c1bece5fca7adc7ddbec61e9");
+
+        let pubkey = secp256k1_recover(
+            &sha256(b"hello world"),
+            RecoveryId::new(1).unwrap(),
+            &signature,
+        )
+        .unwrap();
+
+        assert_eq!(pubkey, Secp256k1Pubkey::new(expected));
+
+        // A different recovery id yields a different public key.
+        let pubkey = secp256k1_recover(
+            &sha256(b"hello world"),
+            RecoveryId::new(0).unwrap(),
+            &signature,
+        )
+        .unwrap();
+
+        assert_eq!(
+            pubkey.to_bytes(),
+            hex::<64>("84a22c8cdf155741957bbbf566721d12a2688ebba8ef91a0c486fcc42636e1f749cb1f1271102b794723751267b58dba33bd34583f021c0025c48eb47958bb7c")
+        );
+
+        let signature = hex("42c2b975009b5ad1868119c7d4ac1954b5699ea218cea204be4feb83039da6c03d6d73cce38710ddbf4fb74bfa11a0ac70352cb96cd5db424c18265efcb7a375");
+        let expected = hex("6c5d5e73124f3c821c0985df787e11b3d018a86add577fa8661613a0d49dde59be34f8ec266c26f94f94497e5b5d1f4159f6c0d2af22ed5ab5ba41d19535499c");
+
+        let pubkey = secp256k1_recover(
+            &sha256(b"ethereum"),
+            RecoveryId::new(0).unwrap(),
+            &signature,
+        )
+        .unwrap();
+
+        assert_eq!(pubkey.as_bytes(), &expected);
+    }
+
+    #[test]
+    fn test_invalid_signature() {
+        let hash = sha256(b"hello world");
+        let recovery_id = RecoveryId::new(0).unwrap();
+
+        // zero `r` and `s` values
+        assert_eq!(
+            secp256k1_recover(&hash, recovery_id, &[0; 64]),
+            Err(Secp256k1RecoverError::InvalidSignature)
+        );
+
+        // `r` and `s` values larger than the curve order
+        assert_eq!(
+            secp256k1_recover(&hash, recovery_id, &[0xff; 64]),
+            Err(Secp256k1RecoverError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn test_recovery_id() {
+        assert_eq!(RecoveryId::new(3).map(RecoveryId::value), Some(3));
+        assert_eq!(RecoveryId::new(4), None);
+        assert_eq!(
+            RecoveryId::try_from(27),
+            Err(Secp256k1RecoverError::InvalidRecoveryId)
+        );
+    }
+
+    #[test]
+    fn test_error_codes() {
+        assert_eq!(
+            Secp256k1RecoverError::from(3),
+            Secp256k1RecoverError::InvalidSignature
+        );
+        assert_eq!(
+            Secp256k1RecoverError::from(4),
+            Secp256k1RecoverError::Unexpected(4)
+        );
+        assert_eq!(u64::from(Secp256k1RecoverError::Unexpected(42)), 42);
+    }
+}
@@ -1,7 +1,13 @@
 //! Information about the network's clock, ticks, slots, etc.
 
 use super::Sysvar;
-use crate::impl_sysvar_get;
+use crate::{impl_sysvar_from_account_info, impl_sysvar_get, pubkey::Pubkey};
+
+/// The address of the clock sysvar (`SysvarC1ock11111111111111111111111111111111`).
+pub const ID: Pubkey = [
+    6, 167, 213, 23, 24, 199, 116, 201, 40, 86, 99, 152, 105, 29, 94, 182, 139, 94, 184, 163, 155,
+    75, 109, 92, 115, 85, 91, 33, 0, 0, 0, 0,
+];
 
 /// The unit of time given to a leader for encoding a block.
 ///
@@ -64,4 +70,5 @@ pub const DEFAULT_MS_PER_SLOT: u64 = 1_000 * DEFAULT_TICKS_PER_SLOT / DEFAULT_TI
 
 impl Sysvar for Clock {
     impl_sysvar_get!(sol_get_clock_sysvar);
+    impl_sysvar_from_account_info!(ID);
 }
@@ -0,0 +1,86 @@
+//! Epoch rewards for the current epoch.
+//!
+//! The sysvar provides information about the partitioned distribution of
+//! staking rewards. Rewards are distributed over a number of blocks at the
+//! beginning of an epoch, during which the sysvar is `active`.
+
+use super::Sysvar;
+use crate::{hash::Hash, impl_sysvar_get};
+
+/// Epoch rewards sysvar data.
+#[repr(C, align(16))]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct EpochRewards {
+    /// The starting block height of the rewards distribution in the current
+    /// epoch.
+    pub distribution_starting_block_height: u64,
+
+    /// Number of partitions in the rewards distribution in the current epoch,
+    /// used to generate an `EpochRewardsHasher`.
+    pub num_partitions: u64,
+
+    /// The blockhash of the parent block of the first block in the epoch, used
+    /// to seed an `EpochRewardsHasher`.
+    pub parent_blockhash: Hash,
+
+    /// The total rewards points calculated for the current epoch, where points
+    /// equals the sum of (delegated stake * credits observed) for all
+    /// delegations.
+    pub total_points: u128,
+
+    /// The total rewards calculated for the current epoch. This may be greater
+    /// than the total `distributed_rewards` at the end of the rewards period,
+    /// due to rounding and inability to deliver rewards smaller than 1 lamport.
+    pub total_rewards: u64,
+
+    /// The rewards currently distributed for the current epoch, in lamports.
+    pub distributed_rewards: u64,
+
+    /// Whether the rewards period (including calculation and distribution) is
+    /// active.
+    pub active: bool,
+}
+
+impl EpochRewards {
+    /// Indicates whether the rewards period is active.
+    #[inline]
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Return the rewards (in lamports) that are yet to be distributed in the
+    /// current epoch.
+    #[inline]
+    pub fn remaining_rewards(&self) -> u64 {
+        self.total_rewards.saturating_sub(self.distributed_rewards)
+    }
+}
+
+impl Sysvar for EpochRewards {
+    impl_sysvar_get!(sol_get_epoch_rewards_sysvar);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout() {
+        assert_eq!(core::mem::size_of::<EpochRewards>(), 96);
+        assert_eq!(core::mem::offset_of!(EpochRewards, total_points), 48);
+        assert_eq!(core::mem::offset_of!(EpochRewards, active), 80);
+    }
+
+    #[test]
+    fn test_remaining_rewards() {
+        let epoch_rewards = EpochRewards {
+            total_rewards: 100,
+            distributed_rewards: 60,
+            active: true,
+            ..Default::default()
+        };
+
+        assert!(epoch_rewards.is_active());
+        assert_eq!(epoch_rewards.remaining_rewards(), 40);
+    }
+}
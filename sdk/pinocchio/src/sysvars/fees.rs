@@ -1,9 +1,16 @@
 //! Calculation of transaction fees.
 
 use super::{clock::DEFAULT_MS_PER_SLOT, Sysvar};
-use crate::impl_sysvar_get;
+use crate::{impl_sysvar_from_account_info, impl_sysvar_get, pubkey::Pubkey};
+
+/// The address of the fees sysvar (`SysvarFees111111111111111111111111111111111`).
+pub const ID: Pubkey = [
+    6, 167, 213, 23, 24, 226, 90, 141, 131, 80, 60, 37, 26, 122, 240, 113, 38, 253, 114, 0, 223,
+    111, 196, 237, 82, 106, 156, 144, 0, 0, 0, 0,
+];
 
 /// Fee calculator for processing transactions
+#[repr(C)]
 #[derive(Debug, Default, Clone, Copy)]
 pub struct FeeCalculator {
     /// The current cost of a signature in lamports.
@@ -73,24 +80,24 @@ impl FeeRateGovernor {
 }
 
 /// Fees sysvar
-#[derive(Debug, Default)]
+///
+/// The sysvar data only contains the fee calculator; the fee rate governor is
+/// not exposed to programs.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default)]
 pub struct Fees {
     /// Fee calculator for processing transactions
     pub fee_calculator: FeeCalculator,
-    /// Fee rate governor
-    pub fee_rate_governor: FeeRateGovernor,
 }
 
 impl Fees {
     /// Create a new instance of the Fees sysvar
-    pub fn new(fee_calculator: FeeCalculator, fee_rate_governor: FeeRateGovernor) -> Self {
-        Self {
-            fee_calculator,
-            fee_rate_governor,
-        }
+    pub fn new(fee_calculator: FeeCalculator) -> Self {
+        Self { fee_calculator }
     }
 }
 
 impl Sysvar for Fees {
     impl_sysvar_get!(sol_get_fees_sysvar);
+    impl_sysvar_from_account_info!(ID);
 }
@@ -0,0 +1,33 @@
+//! Host implementation of the sysvar syscalls used by [`impl_sysvar_get!`].
+//!
+//! With the `std` feature, the syscalls are forwarded to the installed
+//! [`SyscallStubs`](crate::testing::SyscallStubs) based on the sysvar type.
+
+macro_rules! host_syscall {
+    ( $( $sysvar:ty => $name:ident ),* $(,)? ) => {
+        /// Execute the sysvar syscall of the sysvar type `T`.
+        ///
+        /// Sysvar types without a stub are not loaded and return a non-success
+        /// value, so [`impl_sysvar_get!`] can be used with any sysvar type.
+        #[inline(always)]
+        pub fn sysvar_syscall<T: 'static>(var_addr: *mut u8) -> u64 {
+            $(
+                #[cfg(feature = "std")]
+                if core::any::TypeId::of::<T>() == core::any::TypeId::of::<$sysvar>() {
+                    return crate::testing::with_syscall_stubs(|stubs| stubs.$name(var_addr));
+                }
+            )*
+
+            core::hint::black_box(var_addr as *const _ as u64)
+        }
+    };
+}
+
+host_syscall!(
+    super::clock::Clock => sol_get_clock_sysvar,
+    super::epoch_rewards::EpochRewards => sol_get_epoch_rewards_sysvar,
+    super::epoch_schedule::EpochSchedule => sol_get_epoch_schedule_sysvar,
+    super::fees::Fees => sol_get_fees_sysvar,
+    super::last_restart_slot::LastRestartSlot => sol_get_last_restart_slot,
+    super::rent::Rent => sol_get_rent_sysvar,
+);
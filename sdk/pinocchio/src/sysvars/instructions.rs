@@ -0,0 +1,366 @@
+//! Instructions sysvar.
+//!
+//! The instructions sysvar provides access to the serialized instructions of the
+//! currently executing transaction, which allows a program to inspect other
+//! instructions of the same transaction (e.g., to verify that an Ed25519
+//! signature verification instruction was executed before the current one).
+//!
+//! Unlike other sysvars, the instructions sysvar can only be read from its account,
+//! so the account must be passed to the program. The functions in this module
+//! operate directly on the account data without allocating.
+//!
+//! The account data is serialized as follows:
+//!
+//! ```text
+//! [u16] number of instructions (n)
+//! [u16; n] offset of each instruction
+//!
+//! for each instruction:
+//!   [u16] number of accounts (m)
+//!   [(u8, Pubkey); m] account metas (flags and key)
+//!   [Pubkey] program id
+//!   [u16] instruction data length (l)
+//!   [u8; l] instruction data
+//!
+//! [u16] index of the currently executing instruction
+//! ```
+
+use core::mem::size_of;
+
+use crate::{
+    account_info::{AccountInfo, Ref},
+    program_error::ProgramError,
+    pubkey::{Pubkey, PUBKEY_BYTES},
+};
+
+/// The address of the instructions sysvar (`Sysvar1nstructions1111111111111111111111111`).
+pub const ID: Pubkey = [
+    6, 167, 213, 23, 24, 123, 209, 102, 53, 218, 212, 4, 85, 253, 194, 192, 193, 36, 198, 143, 33,
+    86, 117, 165, 219, 186, 203, 95, 8, 0, 0, 0,
+];
+
+/// Bit of the account meta flags indicating whether the account is a signer.
+const IS_SIGNER_BIT: u8 = 0;
+
+/// Bit of the account meta flags indicating whether the account is writable.
+const IS_WRITABLE_BIT: u8 = 1;
+
+/// An account meta of an instruction loaded from the instructions sysvar.
+#[repr(C)]
+pub struct IntrospectedAccountMeta {
+    /// Account flags:
+    ///   * bit `0`: signer
+    ///   * bit `1`: writable
+    flags: u8,
+
+    /// The account key.
+    key: Pubkey,
+}
+
+impl IntrospectedAccountMeta {
+    /// Public key of the account.
+    #[inline(always)]
+    pub fn key(&self) -> &Pubkey {
+        &self.key
+    }
+
+    /// Indicates whether the account is a signer of the transaction.
+    #[inline(always)]
+    pub fn is_signer(&self) -> bool {
+        self.flags & (1 << IS_SIGNER_BIT) != 0
+    }
+
+    /// Indicates whether the account is writable.
+    #[inline(always)]
+    pub fn is_writable(&self) -> bool {
+        self.flags & (1 << IS_WRITABLE_BIT) != 0
+    }
+}
+
+/// An instruction loaded from the instructions sysvar.
+///
+/// This is a zero-copy view over the account data, which holds a borrow of the
+/// data until it is dropped.
+pub struct IntrospectedInstruction<'a> {
+    /// The serialized instruction, starting at the number of accounts and ending
+    /// at the last byte of the instruction data.
+    raw: Ref<'a, [u8]>,
+}
+
+impl IntrospectedInstruction<'_> {
+    /// Number of accounts of the instruction.
+    #[inline(always)]
+    pub fn num_accounts(&self) -> usize {
+        read_u16(&self.raw, 0) as usize
+    }
+
+    /// Account metas of the instruction.
+    #[inline]
+    pub fn account_metas(&self) -> &[IntrospectedAccountMeta] {
+        // SAFETY: `IntrospectedAccountMeta` has an alignment of 1 and the length of
+        // the account metas has been validated when the instruction was loaded.
+        unsafe {
+            core::slice::from_raw_parts(
+                self.raw.as_ptr().add(size_of::<u16>()) as *const IntrospectedAccountMeta,
+                self.num_accounts(),
+            )
+        }
+    }
+
+    /// Return the account meta at the given index, if it exists.
+    #[inline]
+    pub fn get_account_meta_at(&self, index: usize) -> Option<&IntrospectedAccountMeta> {
+        self.account_metas().get(index)
+    }
+
+    /// Program id of the instruction.
+    #[inline]
+    pub fn program_id(&self) -> &Pubkey {
+        let offset = self.program_id_offset();
+        // SAFETY: the length of the instruction has been validated when the
+        // instruction was loaded.
+        unsafe { &*(self.raw.as_ptr().add(offset) as *const Pubkey) }
+    }
+
+    /// Instruction data.
+    #[inline]
+    pub fn data(&self) -> &[u8] {
+        let offset = self.program_id_offset() + PUBKEY_BYTES + size_of::<u16>();
+        &self.raw[offset..]
+    }
+
+    /// Offset of the program id within the serialized instruction.
+    #[inline(always)]
+    fn program_id_offset(&self) -> usize {
+        size_of::<u16>() + self.num_accounts() * size_of::<IntrospectedAccountMeta>()
+    }
+}
+
+/// Load the index of the currently executing instruction.
+///
+/// # Errors
+///
+/// Returns [`ProgramError::UnsupportedSysvar`] if the account is not the
+/// instructions sysvar, and [`ProgramError::InvalidAccountData`] if the account
+/// data is too small.
+#[inline]
+pub fn load_current_index(account_info: &AccountInfo) -> Result<u16, ProgramError> {
+    check_id(account_info)?;
+
+    let data = account_info.try_borrow_data()?;
+
+    if data.len() < size_of::<u16>() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    Ok(read_u16(&data, data.len() - size_of::<u16>()))
+}
+
+/// Load the instruction at the given index.
+///
+/// # Errors
+///
+/// Returns [`ProgramError::UnsupportedSysvar`] if the account is not the
+/// instructions sysvar, [`ProgramError::InvalidArgument`] if the index is out of
+/// bounds and [`ProgramError::InvalidAccountData`] if the account data is
+/// malformed.
+#[inline]
+pub fn load_instruction_at(
+    index: usize,
+    account_info: &AccountInfo,
+) -> Result<IntrospectedInstruction<'_>, ProgramError> {
+    check_id(account_info)?;
+
+    let data = account_info.try_borrow_data()?;
+
+    if data.len() < size_of::<u16>() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let num_instructions = read_u16(&data, 0) as usize;
+
+    if index >= num_instructions {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let offset_position = size_of::<u16>() + index * size_of::<u16>();
+
+    if data.len() < offset_position + size_of::<u16>() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    let start = read_u16(&data, offset_position) as usize;
+    let end = instruction_end(&data, start).ok_or(ProgramError::InvalidAccountData)?;
+
+    Ok(IntrospectedInstruction {
+        raw: Ref::map(data, |data| &data[start..end]),
+    })
+}
+
+/// Load an instruction relative to the currently executing instruction.
+///
+/// For example, an `index_relative_to_current` of `-1` loads the instruction
+/// immediately before the current one, while `0` loads the current instruction.
+///
+/// # Errors
+///
+/// Returns [`ProgramError::UnsupportedSysvar`] if the account is not the
+/// instructions sysvar, [`ProgramError::InvalidArgument`] if the resulting index
+/// is out of bounds and [`ProgramError::InvalidAccountData`] if the account data
+/// is malformed.
+#[inline]
+pub fn get_instruction_relative(
+    index_relative_to_current: i64,
+    account_info: &AccountInfo,
+) -> Result<IntrospectedInstruction<'_>, ProgramError> {
+    let current_index = load_current_index(account_info)? as i64;
+    let index = current_index.saturating_add(index_relative_to_current);
+
+    if index < 0 {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    load_instruction_at(index as usize, account_info)
+}
+
+/// Check that the account is the instructions sysvar.
+#[inline(always)]
+fn check_id(account_info: &AccountInfo) -> Result<(), ProgramError> {
+    if account_info.key() != &ID {
+        return Err(ProgramError::UnsupportedSysvar);
+    }
+    Ok(())
+}
+
+/// Return the end offset of the instruction starting at `start`, if the
+/// instruction is within the bounds of the data.
+#[inline(always)]
+fn instruction_end(data: &[u8], start: usize) -> Option<usize> {
+    let accounts_start = start.checked_add(size_of::<u16>())?;
+
+    if data.len() < accounts_start {
+        return None;
+    }
+
+    let num_accounts = read_u16(data, start) as usize;
+    let data_len_offset =
+        accounts_start + num_accounts * size_of::<IntrospectedAccountMeta>() + PUBKEY_BYTES;
+
+    if data.len() < data_len_offset + size_of::<u16>() {
+        return None;
+    }
+
+    let end = data_len_offset + size_of::<u16>() + read_u16(data, data_len_offset) as usize;
+    (end <= data.len()).then_some(end)
+}
+
+/// Read a little-endian `u16` at the given offset.
+///
+/// The caller must ensure that the offset is within bounds.
+#[inline(always)]
+fn read_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([data[offset], data[offset + 1]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sysvars::tests::sysvar_account;
+
+    /// Size of the buffer used to create the sysvar account.
+    const BUFFER_LEN: usize = 512;
+
+    /// Serialize two instructions followed by the current index `1`.
+    fn instructions_data(data: &mut [u8]) -> usize {
+        let mut offset = 0;
+        let mut write = |bytes: &[u8]| {
+            data[offset..offset + bytes.len()].copy_from_slice(bytes);
+            offset += bytes.len();
+        };
+
+        // header: 2 instructions at offsets 6 and 78
+        write(&2u16.to_le_bytes());
+        write(&6u16.to_le_bytes());
+        write(&78u16.to_le_bytes());
+
+        // instruction 0: 1 writable signer account, 3 bytes of data
+        write(&1u16.to_le_bytes());
+        write(&[0b11]);
+        write(&[1; 32]);
+        write(&[2; 32]);
+        write(&3u16.to_le_bytes());
+        write(&[7, 8, 9]);
+
+        // instruction 1: no accounts, no data
+        write(&0u16.to_le_bytes());
+        write(&[3; 32]);
+        write(&0u16.to_le_bytes());
+
+        // current index
+        write(&1u16.to_le_bytes());
+
+        offset
+    }
+
+    #[test]
+    fn test_load_instructions() {
+        let mut data = [0u8; 256];
+        let len = instructions_data(&mut data);
+
+        let mut buffer = [0u64; BUFFER_LEN];
+        let account_info = sysvar_account(&mut buffer, &ID, &data[..len]);
+
+        assert_eq!(load_current_index(&account_info), Ok(1));
+
+        let instruction = load_instruction_at(0, &account_info).unwrap();
+        assert_eq!(instruction.program_id(), &[2; 32]);
+        assert_eq!(instruction.num_accounts(), 1);
+        assert_eq!(instruction.data(), &[7, 8, 9]);
+
+        let meta = instruction.get_account_meta_at(0).unwrap();
+        assert_eq!(meta.key(), &[1; 32]);
+        assert!(meta.is_signer());
+        assert!(meta.is_writable());
+        assert!(instruction.get_account_meta_at(1).is_none());
+
+        let instruction = get_instruction_relative(0, &account_info).unwrap();
+        assert_eq!(instruction.program_id(), &[3; 32]);
+        assert!(instruction.account_metas().is_empty());
+        assert!(instruction.data().is_empty());
+
+        let previous = get_instruction_relative(-1, &account_info).unwrap();
+        assert_eq!(previous.program_id(), &[2; 32]);
+
+        assert!(matches!(
+            get_instruction_relative(1, &account_info),
+            Err(ProgramError::InvalidArgument)
+        ));
+        assert!(matches!(
+            get_instruction_relative(-2, &account_info),
+            Err(ProgramError::InvalidArgument)
+        ));
+    }
+
+    #[test]
+    fn test_invalid_account() {
+        let mut data = [0u8; 256];
+        let len = instructions_data(&mut data);
+
+        let mut buffer = [0u64; BUFFER_LEN];
+        let account_info = sysvar_account(&mut buffer, &[0; 32], &data[..len]);
+
+        assert_eq!(
+            load_current_index(&account_info),
+            Err(ProgramError::UnsupportedSysvar)
+        );
+
+        // truncated instruction data
+        let mut buffer = [0u64; BUFFER_LEN];
+        let account_info = sysvar_account(&mut buffer, &ID, &data[..60]);
+
+        assert!(matches!(
+            load_instruction_at(0, &account_info),
+            Err(ProgramError::InvalidAccountData)
+        ));
+    }
+}
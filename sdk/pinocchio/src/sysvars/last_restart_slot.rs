@@ -0,0 +1,49 @@
+//! Information about the last restart slot (hard fork).
+//!
+//! The sysvar holds the most recent slot in which the cluster was restarted.
+//! Programs relying on time-sensitive state (e.g. oracle prices) can use it to
+//! reject data that was produced before a restart.
+
+use super::{clock::Slot, Sysvar};
+use crate::impl_sysvar_get;
+
+/// Last restart slot sysvar data.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct LastRestartSlot {
+    /// The last restart `Slot`.
+    pub last_restart_slot: Slot,
+}
+
+impl LastRestartSlot {
+    /// Indicates whether the given slot precedes the last cluster restart.
+    ///
+    /// The restart slot is the slot of the snapshot from which the cluster was
+    /// restarted, and blocks produced after the restart start at the next slot.
+    /// State written in the restart slot itself was therefore produced before
+    /// the restart, so the restart slot is considered to precede it.
+    #[inline]
+    pub fn is_before_restart(&self, slot: Slot) -> bool {
+        slot <= self.last_restart_slot
+    }
+}
+
+impl Sysvar for LastRestartSlot {
+    impl_sysvar_get!(sol_get_last_restart_slot);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_before_restart() {
+        let last_restart_slot = LastRestartSlot {
+            last_restart_slot: 100,
+        };
+
+        assert!(last_restart_slot.is_before_restart(99));
+        assert!(last_restart_slot.is_before_restart(100));
+        assert!(!last_restart_slot.is_before_restart(101));
+    }
+}
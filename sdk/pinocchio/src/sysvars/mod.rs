@@ -1,10 +1,27 @@
 //! Provides access to cluster system accounts.
 
-use crate::program_error::ProgramError;
+use crate::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
 
 pub mod clock;
+pub mod epoch_rewards;
+pub mod epoch_schedule;
 pub mod fees;
+pub mod instructions;
+pub mod last_restart_slot;
 pub mod rent;
+pub mod slot_hashes;
+pub mod stake_history;
+
+#[cfg(not(target_os = "solana"))]
+#[doc(hidden)]
+pub mod host;
+
+/// Return value of `sol_get_sysvar` when the requested range is outside the
+/// sysvar data.
+pub const OFFSET_LENGTH_EXCEEDS_SYSVAR: u64 = 1;
+
+/// Return value of `sol_get_sysvar` when the sysvar is not found.
+pub const SYSVAR_NOT_FOUND: u64 = 2;
 
 /// A type that holds sysvar data.
 pub trait Sysvar: Default + Sized {
@@ -19,6 +36,18 @@ pub trait Sysvar: Default + Sized {
     fn get() -> Result<Self, ProgramError> {
         Err(ProgramError::UnsupportedSysvar)
     }
+
+    /// Load the sysvar from its account.
+    ///
+    /// This returns a copy of the account data, since the account data of some
+    /// sysvars does not include the trailing padding of their type. The account
+    /// must be the sysvar account and its data must have the expected length.
+    ///
+    /// Not all sysvars support this method. If not, it returns
+    /// [`ProgramError::UnsupportedSysvar`].
+    fn from_account_info(_account_info: &AccountInfo) -> Result<Self, ProgramError> {
+        Err(ProgramError::UnsupportedSysvar)
+    }
 }
 
 /// Implements the [`Sysvar::get`] method for both SBF and host targets.
@@ -33,7 +62,7 @@ macro_rules! impl_sysvar_get {
             let result = unsafe { $crate::syscalls::$syscall_name(var_addr) };
 
             #[cfg(not(target_os = "solana"))]
-            let result = core::hint::black_box(var_addr as *const _ as u64);
+            let result = $crate::sysvars::host::sysvar_syscall::<Self>(var_addr);
 
             match result {
                 $crate::SUCCESS => Ok(var),
@@ -42,3 +71,235 @@ macro_rules! impl_sysvar_get {
         }
     };
 }
+
+/// Implements the [`Sysvar::from_account_info`] method.
+///
+/// The `$id` is the address of the sysvar account, whose data must be a valid
+/// representation of the sysvar type. The optional `$len` is the length of the
+/// account data when it does not include the trailing padding of the type;
+/// otherwise, the account data must have the size of the type.
+#[macro_export]
+macro_rules! impl_sysvar_from_account_info {
+    ($id:expr) => {
+        $crate::impl_sysvar_from_account_info!($id, core::mem::size_of::<Self>());
+    };
+    ($id:expr, $len:expr) => {
+        fn from_account_info(
+            account_info: &$crate::account_info::AccountInfo,
+        ) -> Result<Self, $crate::program_error::ProgramError> {
+            if account_info.key() != &$id {
+                return Err($crate::program_error::ProgramError::InvalidArgument);
+            }
+
+            let data = account_info.try_borrow_data()?;
+
+            if data.len() != $len || data.len() > core::mem::size_of::<Self>() {
+                return Err($crate::program_error::ProgramError::InvalidAccountData);
+            }
+
+            let mut var = Self::default();
+            // the account data might be unaligned and shorter than the type
+            unsafe {
+                core::ptr::copy_nonoverlapping(
+                    data.as_ptr(),
+                    &mut var as *mut Self as *mut u8,
+                    data.len(),
+                );
+            }
+
+            Ok(var)
+        }
+    };
+}
+
+/// Copy `length` bytes of the sysvar data, starting at `offset`, into `dst`.
+///
+/// This allows reading a portion of a sysvar (e.g., a single entry of a large
+/// sysvar such as [`slot_hashes`] or [`stake_history`]) without copying all of
+/// its data, and without the sysvar account being passed to the program.
+///
+/// # Errors
+///
+/// Returns [`ProgramError::InvalidArgument`] if `dst` is smaller than `length` or
+/// the requested range is outside the sysvar data, and
+/// [`ProgramError::UnsupportedSysvar`] if the sysvar is not found.
+#[inline]
+pub fn get_sysvar(
+    dst: &mut [u8],
+    sysvar_id: &Pubkey,
+    offset: u64,
+    length: u64,
+) -> Result<(), ProgramError> {
+    if (dst.len() as u64) < length {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    #[cfg(target_os = "solana")]
+    let result = unsafe {
+        crate::syscalls::sol_get_sysvar(
+            sysvar_id as *const _ as *const u8,
+            dst.as_mut_ptr(),
+            offset,
+            length,
+        )
+    };
+
+    #[cfg(all(not(target_os = "solana"), feature = "std"))]
+    let result = crate::testing::with_syscall_stubs(|stubs| {
+        stubs.sol_get_sysvar(sysvar_id, &mut dst[..length as usize], offset)
+    });
+
+    #[cfg(all(not(target_os = "solana"), not(feature = "std")))]
+    let result = {
+        core::hint::black_box((dst, sysvar_id, offset));
+        SYSVAR_NOT_FOUND
+    };
+
+    match result {
+        crate::SUCCESS => Ok(()),
+        OFFSET_LENGTH_EXCEEDS_SYSVAR => Err(ProgramError::InvalidArgument),
+        _ => Err(ProgramError::UnsupportedSysvar),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::{clock::Clock, fees::Fees, rent::Rent, Sysvar};
+    use crate::{
+        account_info::{Account, AccountInfo},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    };
+
+    /// Create an account with the given key and data in the buffer.
+    ///
+    /// The account header is laid out as: borrow state and flags (4 bytes),
+    /// original data length (4 bytes), key, owner, lamports and data length.
+    pub(crate) fn sysvar_account(buffer: &mut [u64], key: &Pubkey, data: &[u8]) -> AccountInfo {
+        let header = core::mem::size_of::<Account>();
+        let bytes = unsafe {
+            core::slice::from_raw_parts_mut(buffer.as_mut_ptr() as *mut u8, buffer.len() * 8)
+        };
+
+        bytes[8..40].copy_from_slice(key);
+        bytes[header - 8..header].copy_from_slice(&(data.len() as u64).to_le_bytes());
+        bytes[header..header + data.len()].copy_from_slice(data);
+
+        AccountInfo {
+            raw: buffer.as_mut_ptr() as *mut Account,
+        }
+    }
+
+    #[test]
+    fn test_get_sysvar() {
+        let mut dst = [0u8; 8];
+
+        assert_eq!(
+            super::get_sysvar(&mut dst, &super::clock::ID, 0, 16),
+            Err(ProgramError::InvalidArgument)
+        );
+    }
+
+    #[test]
+    fn test_from_account_info() {
+        let mut data = [0u8; 40];
+        data[..8].copy_from_slice(&100u64.to_le_bytes());
+        data[32..].copy_from_slice(&1_700_000_000i64.to_le_bytes());
+
+        let mut buffer = [0u64; 32];
+        let account_info = sysvar_account(&mut buffer, &super::clock::ID, &data);
+        let clock = Clock::from_account_info(&account_info).unwrap();
+
+        assert_eq!(clock.slot, 100);
+        assert_eq!(clock.unix_timestamp, 1_700_000_000);
+
+        let mut data = [0u8; 17];
+        data[..8].copy_from_slice(&3480u64.to_le_bytes());
+        data[8..16].copy_from_slice(&2.0f64.to_le_bytes());
+        data[16] = 50;
+
+        let mut buffer = [0u64; 32];
+        let account_info = sysvar_account(&mut buffer, &super::rent::ID, &data);
+        let rent = Rent::from_account_info(&account_info).unwrap();
+
+        assert_eq!(rent.lamports_per_byte_year, 3480);
+        assert_eq!(rent.burn_percent, 50);
+        assert_eq!(rent.minimum_balance(0), 890_880);
+
+        let mut buffer = [0u64; 32];
+        let account_info = sysvar_account(&mut buffer, &super::fees::ID, &5000u64.to_le_bytes());
+        let fees = Fees::from_account_info(&account_info).unwrap();
+
+        assert_eq!(fees.fee_calculator.lamports_per_signature, 5000);
+    }
+
+    #[test]
+    fn test_rent_from_account_info() {
+        let mut data = [0u8; 17];
+        data[..8].copy_from_slice(&3480u64.to_le_bytes());
+        data[8..16].copy_from_slice(&2.0f64.to_le_bytes());
+        data[16] = 50;
+
+        let mut buffer = [0u64; 32];
+        let account_info = sysvar_account(&mut buffer, &super::rent::ID, &data[..16]);
+
+        assert!(matches!(
+            Rent::from_account_info(&account_info),
+            Err(ProgramError::InvalidAccountData)
+        ));
+
+        // the account data does not include the trailing padding
+        let mut buffer = [0u64; 32];
+        let account_info = sysvar_account(&mut buffer, &super::rent::ID, &[0u8; 24]);
+
+        assert!(matches!(
+            Rent::from_account_info(&account_info),
+            Err(ProgramError::InvalidAccountData)
+        ));
+
+        let mut buffer = [0u64; 32];
+        let account_info = sysvar_account(&mut buffer, &super::clock::ID, &data);
+
+        assert!(matches!(
+            Rent::from_account_info(&account_info),
+            Err(ProgramError::InvalidArgument)
+        ));
+
+        let mut buffer = [0u64; 32];
+        let account_info = sysvar_account(&mut buffer, &super::rent::ID, &data);
+        let rent = <Rent as Sysvar>::from_account_info(&account_info).unwrap();
+
+        assert_eq!(rent.lamports_per_byte_year, 3480);
+        assert_eq!(rent.exemption_threshold, 2.0);
+        assert_eq!(rent.burn_percent, 50);
+    }
+
+    #[test]
+    fn test_from_account_info_invalid() {
+        let data = [0u8; 40];
+
+        let mut buffer = [0u64; 32];
+        let account_info = sysvar_account(&mut buffer, &super::rent::ID, &data);
+
+        assert!(matches!(
+            Clock::from_account_info(&account_info),
+            Err(ProgramError::InvalidArgument)
+        ));
+
+        let mut buffer = [0u64; 32];
+        let account_info = sysvar_account(&mut buffer, &super::clock::ID, &data[..32]);
+
+        assert!(matches!(
+            Clock::from_account_info(&account_info),
+            Err(ProgramError::InvalidAccountData)
+        ));
+
+        let mut buffer = [0u64; 32];
+        let account_info = sysvar_account(&mut buffer, &super::clock::ID, &[0u8; 48]);
+
+        assert!(matches!(
+            Clock::from_account_info(&account_info),
+            Err(ProgramError::InvalidAccountData)
+        ));
+    }
+}
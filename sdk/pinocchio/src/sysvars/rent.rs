@@ -3,7 +3,18 @@
 //! This is required for the rent sysvar implementation.
 
 use super::Sysvar;
-use crate::impl_sysvar_get;
+use crate::{impl_sysvar_from_account_info, impl_sysvar_get, pubkey::Pubkey};
+
+/// The address of the rent sysvar (`SysvarRent111111111111111111111111111111111`).
+pub const ID: Pubkey = [
+    6, 167, 213, 23, 25, 44, 92, 81, 33, 140, 201, 76, 61, 74, 241, 127, 88, 218, 238, 8, 155, 161,
+    253, 68, 227, 219, 217, 138, 0, 0, 0, 0,
+];
+
+/// Length of the rent sysvar account data.
+///
+/// The account data does not include the trailing padding of [`Rent`].
+const RENT_LEN: usize = 17;
 
 /// Default rental rate in lamports/byte-year.
 ///
@@ -144,6 +155,7 @@ impl Rent {
 
 impl Sysvar for Rent {
     impl_sysvar_get!(sol_get_rent_sysvar);
+    impl_sysvar_from_account_info!(ID, RENT_LEN);
 }
 
 /// The return value of [`Rent::due`].
@@ -0,0 +1,242 @@
+//! The most recent hashes of a slot's parent banks.
+//!
+//! The slot hashes sysvar cannot be loaded in full through a syscall. It can either
+//! be read from its account, using [`SlotHashes`] as a zero-copy view over the account
+//! data, or one entry at a time with [`get_entry`], which does not require the
+//! account to be passed to the program. Both avoid copying the (roughly 20KB)
+//! sysvar data.
+
+use core::mem::{align_of, size_of};
+
+use super::{clock::Slot, get_sysvar};
+use crate::{
+    account_info::{AccountInfo, Ref},
+    hash::Hash,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+/// The address of the slot hashes sysvar (`SysvarS1otHashes111111111111111111111111111`).
+pub const ID: Pubkey = [
+    6, 167, 213, 23, 25, 47, 10, 175, 198, 242, 101, 227, 251, 119, 204, 122, 218, 130, 197, 41,
+    208, 190, 59, 19, 110, 45, 0, 85, 32, 0, 0, 0,
+];
+
+/// The maximum number of slot hashes stored in the sysvar.
+pub const MAX_ENTRIES: usize = 512;
+
+/// A slot hash entry.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SlotHash {
+    /// The slot of the entry.
+    pub slot: Slot,
+
+    /// The hash of the slot.
+    pub hash: Hash,
+}
+
+/// A view over the slot hashes sysvar account data.
+///
+/// Entries are sorted by slot in descending order, so the most recent slot is
+/// the first entry. The view holds a borrow of the account data until it is
+/// dropped.
+pub struct SlotHashes<'a> {
+    /// The slot hash entries.
+    entries: Ref<'a, [SlotHash]>,
+}
+
+impl<'a> SlotHashes<'a> {
+    /// Return a `SlotHashes` view from the given account info.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProgramError::UnsupportedSysvar`] if the account is not the slot
+    /// hashes sysvar and [`ProgramError::InvalidAccountData`] if the account data
+    /// is malformed.
+    #[inline]
+    pub fn from_account_info(account_info: &'a AccountInfo) -> Result<Self, ProgramError> {
+        if account_info.key() != &ID {
+            return Err(ProgramError::UnsupportedSysvar);
+        }
+
+        let entries = Ref::filter_map(account_info.try_borrow_data()?, |data| unsafe {
+            load_entries::<SlotHash>(data)
+        })
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        Ok(Self { entries })
+    }
+
+    /// Return the slot hash entries.
+    #[inline(always)]
+    pub fn entries(&self) -> &[SlotHash] {
+        &self.entries
+    }
+
+    /// Return the number of entries.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Indicates whether there are no entries.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Return the hash of the given slot, if it is present.
+    #[inline]
+    pub fn get(&self, slot: Slot) -> Option<&Hash> {
+        self.position(slot).map(|index| &self.entries[index].hash)
+    }
+
+    /// Return the index of the entry of the given slot, if it is present.
+    #[inline]
+    pub fn position(&self, slot: Slot) -> Option<usize> {
+        self.entries
+            .binary_search_by(|entry| slot.cmp(&entry.slot))
+            .ok()
+    }
+}
+
+/// Return the number of entries of the slot hashes sysvar.
+///
+/// This reads the sysvar through the `sol_get_sysvar` syscall, so the sysvar
+/// account does not need to be passed to the program.
+#[inline]
+pub fn get_len() -> Result<usize, ProgramError> {
+    get_entries_len(&ID)
+}
+
+/// Return the slot hash entry at the given index.
+///
+/// This reads only the requested entry through the `sol_get_sysvar` syscall, so
+/// the sysvar account does not need to be passed to the program.
+///
+/// # Errors
+///
+/// Returns [`ProgramError::InvalidArgument`] if the index is out of bounds.
+#[inline]
+pub fn get_entry(index: usize) -> Result<SlotHash, ProgramError> {
+    // SAFETY: any sequence of bytes is a valid `SlotHash`.
+    unsafe { get_entry_at(&ID, index) }
+}
+
+/// Return the number of entries of a sysvar holding a length-prefixed list.
+#[inline(always)]
+pub(crate) fn get_entries_len(sysvar_id: &Pubkey) -> Result<usize, ProgramError> {
+    let mut len = [0u8; size_of::<u64>()];
+    get_sysvar(&mut len, sysvar_id, 0, size_of::<u64>() as u64)?;
+    Ok(u64::from_le_bytes(len) as usize)
+}
+
+/// Return the entry at the given index of a sysvar holding a length-prefixed
+/// list.
+///
+/// # Safety
+///
+/// The caller must ensure that any sequence of bytes is a valid `T`.
+#[inline(always)]
+pub(crate) unsafe fn get_entry_at<T: Default>(
+    sysvar_id: &Pubkey,
+    index: usize,
+) -> Result<T, ProgramError> {
+    if index >= get_entries_len(sysvar_id)? {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let mut entry = T::default();
+    let bytes = core::slice::from_raw_parts_mut(&mut entry as *mut T as *mut u8, size_of::<T>());
+    let offset = size_of::<u64>() + index * size_of::<T>();
+
+    get_sysvar(bytes, sysvar_id, offset as u64, size_of::<T>() as u64)?;
+
+    Ok(entry)
+}
+
+/// Interpret the sysvar data as a length-prefixed list of entries.
+///
+/// Returns `None` if the data is not aligned or it is too small to contain the
+/// number of entries specified by its length prefix.
+///
+/// # Safety
+///
+/// The caller must ensure that any sequence of bytes is a valid `T`.
+#[inline(always)]
+pub(crate) unsafe fn load_entries<T>(data: &[u8]) -> Option<&[T]> {
+    if data.len() < size_of::<u64>() || data.as_ptr() as usize % align_of::<T>() != 0 {
+        return None;
+    }
+
+    let len = u64::from_le_bytes(data[..size_of::<u64>()].try_into().unwrap()) as usize;
+
+    if (data.len() - size_of::<u64>()) / size_of::<T>() < len {
+        return None;
+    }
+
+    Some(core::slice::from_raw_parts(
+        data.as_ptr().add(size_of::<u64>()) as *const T,
+        len,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sysvars::tests::sysvar_account;
+
+    #[test]
+    fn test_get() {
+        // 3 entries for slots 30, 20 and 10
+        let mut data = [0u8; 8 + 3 * size_of::<SlotHash>()];
+        data[..8].copy_from_slice(&3u64.to_le_bytes());
+
+        for (i, slot) in [30u64, 20, 10].iter().enumerate() {
+            let offset = 8 + i * size_of::<SlotHash>();
+            data[offset..offset + 8].copy_from_slice(&slot.to_le_bytes());
+            data[offset + 8..offset + 40].copy_from_slice(&[*slot as u8; 32]);
+        }
+
+        let mut buffer = [0u64; 64];
+        let account_info = sysvar_account(&mut buffer, &ID, &data);
+        let slot_hashes = SlotHashes::from_account_info(&account_info).unwrap();
+
+        assert_eq!(slot_hashes.len(), 3);
+        assert_eq!(slot_hashes.get(30), Some(&[30; 32]));
+        assert_eq!(slot_hashes.get(10), Some(&[10; 32]));
+        assert_eq!(slot_hashes.position(20), Some(1));
+        assert_eq!(slot_hashes.get(15), None);
+        assert_eq!(slot_hashes.get(40), None);
+    }
+
+    #[test]
+    fn test_invalid_account() {
+        let mut data = [0u8; 8 + size_of::<SlotHash>()];
+        data[..8].copy_from_slice(&2u64.to_le_bytes());
+
+        let mut buffer = [0u64; 64];
+        let account_info = sysvar_account(&mut buffer, &ID, &data);
+
+        assert!(matches!(
+            SlotHashes::from_account_info(&account_info),
+            Err(ProgramError::InvalidAccountData)
+        ));
+
+        let mut buffer = [0u64; 64];
+        let account_info = sysvar_account(&mut buffer, &[0; 32], &data);
+
+        assert!(matches!(
+            SlotHashes::from_account_info(&account_info),
+            Err(ProgramError::UnsupportedSysvar)
+        ));
+    }
+
+    #[test]
+    fn test_get_entry() {
+        // the sysvar cannot be read off-chain
+        assert_eq!(get_len(), Err(ProgramError::UnsupportedSysvar));
+        assert_eq!(get_entry(0), Err(ProgramError::UnsupportedSysvar));
+    }
+}
@@ -0,0 +1,192 @@
+//! History of stake activations and de-activations.
+//!
+//! The stake history sysvar cannot be loaded in full through a syscall. It can either
+//! be read from its account, using [`StakeHistory`] as a zero-copy view over the account
+//! data, or one entry at a time with [`get_entry`], which does not require the
+//! account to be passed to the program. Both avoid copying the (roughly 16KB)
+//! sysvar data.
+
+use super::{
+    clock::Epoch,
+    slot_hashes::{get_entries_len, get_entry_at, load_entries},
+};
+use crate::{
+    account_info::{AccountInfo, Ref},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+/// The address of the stake history sysvar (`SysvarStakeHistory1111111111111111111111111`).
+pub const ID: Pubkey = [
+    6, 167, 213, 23, 25, 53, 132, 208, 254, 237, 155, 179, 67, 29, 19, 32, 107, 229, 68, 40, 27,
+    87, 184, 86, 108, 197, 55, 95, 244, 0, 0, 0,
+];
+
+/// The maximum number of entries stored in the sysvar.
+pub const MAX_ENTRIES: usize = 512;
+
+/// The stake activation and deactivation of an epoch.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct StakeHistoryEntry {
+    /// Effective stake at this epoch.
+    pub effective: u64,
+
+    /// Sum of portion of activations that are still warming up.
+    pub activating: u64,
+
+    /// Requested to be cooled down, not fully deactivated yet.
+    pub deactivating: u64,
+}
+
+/// A stake history entry together with its epoch.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct EpochStakeHistoryEntry {
+    /// The epoch of the entry.
+    pub epoch: Epoch,
+
+    /// The stake history of the epoch.
+    pub entry: StakeHistoryEntry,
+}
+
+/// A view over the stake history sysvar account data.
+///
+/// Entries are sorted by epoch in descending order, so the most recent epoch is
+/// the first entry. The view holds a borrow of the account data until it is
+/// dropped.
+pub struct StakeHistory<'a> {
+    /// The stake history entries.
+    entries: Ref<'a, [EpochStakeHistoryEntry]>,
+}
+
+impl<'a> StakeHistory<'a> {
+    /// Return a `StakeHistory` view from the given account info.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProgramError::UnsupportedSysvar`] if the account is not the stake
+    /// history sysvar and [`ProgramError::InvalidAccountData`] if the account data
+    /// is malformed.
+    #[inline]
+    pub fn from_account_info(account_info: &'a AccountInfo) -> Result<Self, ProgramError> {
+        if account_info.key() != &ID {
+            return Err(ProgramError::UnsupportedSysvar);
+        }
+
+        let entries = Ref::filter_map(account_info.try_borrow_data()?, |data| unsafe {
+            load_entries::<EpochStakeHistoryEntry>(data)
+        })
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+
+        Ok(Self { entries })
+    }
+
+    /// Return the stake history entries.
+    #[inline(always)]
+    pub fn entries(&self) -> &[EpochStakeHistoryEntry] {
+        &self.entries
+    }
+
+    /// Return the number of entries.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Indicates whether there are no entries.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Return the stake history of the given epoch, if it is present.
+    #[inline]
+    pub fn get(&self, epoch: Epoch) -> Option<&StakeHistoryEntry> {
+        self.entries
+            .binary_search_by(|entry| epoch.cmp(&entry.epoch))
+            .ok()
+            .map(|index| &self.entries[index].entry)
+    }
+}
+
+/// Return the number of entries of the stake history sysvar.
+///
+/// This reads the sysvar through the `sol_get_sysvar` syscall, so the sysvar
+/// account does not need to be passed to the program.
+#[inline]
+pub fn get_len() -> Result<usize, ProgramError> {
+    get_entries_len(&ID)
+}
+
+/// Return the stake history entry at the given index.
+///
+/// This reads only the requested entry through the `sol_get_sysvar` syscall, so
+/// the sysvar account does not need to be passed to the program.
+///
+/// # Errors
+///
+/// Returns [`ProgramError::InvalidArgument`] if the index is out of bounds.
+#[inline]
+pub fn get_entry(index: usize) -> Result<EpochStakeHistoryEntry, ProgramError> {
+    // SAFETY: any sequence of bytes is a valid `EpochStakeHistoryEntry`.
+    unsafe { get_entry_at(&ID, index) }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::mem::size_of;
+
+    use super::*;
+    use crate::sysvars::tests::sysvar_account;
+
+    #[test]
+    fn test_get() {
+        // 4 entries for epochs 4 to 1
+        let mut data = [0u8; 8 + 4 * size_of::<EpochStakeHistoryEntry>()];
+        data[..8].copy_from_slice(&4u64.to_le_bytes());
+
+        for (i, epoch) in (1u64..=4).rev().enumerate() {
+            let offset = 8 + i * size_of::<EpochStakeHistoryEntry>();
+            data[offset..offset + 8].copy_from_slice(&epoch.to_le_bytes());
+            data[offset + 8..offset + 16].copy_from_slice(&(epoch * 100).to_le_bytes());
+            data[offset + 16..offset + 24].copy_from_slice(&(epoch * 10).to_le_bytes());
+            data[offset + 24..offset + 32].copy_from_slice(&epoch.to_le_bytes());
+        }
+
+        let mut buffer = [0u64; 64];
+        let account_info = sysvar_account(&mut buffer, &ID, &data);
+        let stake_history = StakeHistory::from_account_info(&account_info).unwrap();
+
+        assert_eq!(stake_history.len(), 4);
+        assert_eq!(stake_history.entries()[0].epoch, 4);
+        assert_eq!(
+            stake_history.get(2),
+            Some(&StakeHistoryEntry {
+                effective: 200,
+                activating: 20,
+                deactivating: 2,
+            })
+        );
+        assert_eq!(stake_history.get(0), None);
+        assert_eq!(stake_history.get(5), None);
+
+        // the view holds a borrow of the account data
+        assert!(account_info.try_borrow_mut_data().is_err());
+        drop(stake_history);
+        assert!(account_info.try_borrow_mut_data().is_ok());
+    }
+
+    #[test]
+    fn test_invalid_account() {
+        let data = 0u64.to_le_bytes();
+
+        let mut buffer = [0u64; 64];
+        let account_info = sysvar_account(&mut buffer, &[0; 32], &data);
+
+        assert!(matches!(
+            StakeHistory::from_account_info(&account_info),
+            Err(ProgramError::UnsupportedSysvar)
+        ));
+    }
+}
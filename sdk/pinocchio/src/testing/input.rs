@@ -0,0 +1,667 @@
+//! Serialization of the entrypoint input buffer.
+
+use std::vec::Vec;
+
+use crate::{
+    account_info::{Account, AccountInfo, GET_LEN_MASK, MAX_PERMITTED_DATA_INCREASE, SET_LEN_MASK},
+    entrypoint::{deserialize, deserialize_with_remaining, RemainingAccounts},
+    lazy_entrypoint::InstructionContext,
+    pubkey::Pubkey,
+    ProgramResult, BPF_ALIGN_OF_U128, MAX_TX_ACCOUNTS, NON_DUP_MARKER,
+};
+
+/// Rent epoch value serialized for all accounts.
+///
+/// The runtime serializes `u64::MAX` since accounts are rent exempt.
+const RENT_EXEMPT_RENT_EPOCH: u64 = u64::MAX;
+
+/// An account to be serialized in the input buffer.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MockAccount {
+    /// Public key of the account.
+    pub key: Pubkey,
+
+    /// Program that owns the account.
+    pub owner: Pubkey,
+
+    /// The lamports in the account.
+    pub lamports: u64,
+
+    /// Data held in the account.
+    pub data: Vec<u8>,
+
+    /// Indicates whether the transaction was signed by the account.
+    pub is_signer: bool,
+
+    /// Indicates whether the account is writable.
+    pub is_writable: bool,
+
+    /// Indicates whether the account represents a program.
+    pub executable: bool,
+}
+
+impl MockAccount {
+    /// Create a read-only, non-signer account.
+    pub fn new(key: Pubkey, owner: Pubkey, lamports: u64, data: Vec<u8>) -> Self {
+        Self {
+            key,
+            owner,
+            lamports,
+            data,
+            ..Default::default()
+        }
+    }
+
+    /// Mark the account as a signer.
+    pub fn signer(mut self) -> Self {
+        self.is_signer = true;
+        self
+    }
+
+    /// Mark the account as writable.
+    pub fn writable(mut self) -> Self {
+        self.is_writable = true;
+        self
+    }
+
+    /// Mark the account as executable.
+    pub fn executable(mut self) -> Self {
+        self.executable = true;
+        self
+    }
+}
+
+/// An entry of the accounts section of the input buffer.
+#[derive(Clone, Debug)]
+enum InputAccount {
+    /// A (non-duplicated) account.
+    Account(MockAccount),
+
+    /// The index of the duplicated account.
+    Duplicate(u8),
+}
+
+/// Location of an account in the serialized input buffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SerializedAccount {
+    /// Offset of a (non-duplicated) account.
+    Account(usize),
+
+    /// The index of the duplicated account.
+    Duplicate(u8),
+}
+
+/// Builder of the entrypoint input buffer.
+///
+/// The buffer follows the (aligned) serialization format used by the runtime:
+///
+/// ```text
+/// [u64] number of accounts
+///
+/// for each account:
+///   non-duplicate:
+///     [u8] non-duplicate marker (`u8::MAX`)
+///     [u8] is signer
+///     [u8] is writable
+///     [u8] executable
+///     [u8; 4] padding
+///     [Pubkey] key
+///     [Pubkey] owner
+///     [u64] lamports
+///     [u64] data length
+///     [u8; data length] data
+///     [u8; MAX_PERMITTED_DATA_INCREASE] realloc padding
+///     padding to 8-byte alignment
+///     [u64] rent epoch
+///   duplicate:
+///     [u8] index of the duplicated account
+///     [u8; 7] padding
+///
+/// [u64] instruction data length
+/// [u8; instruction data length] instruction data
+/// [Pubkey] program id
+/// ```
+#[derive(Clone, Debug)]
+pub struct InputBuilder {
+    /// Program id of the instruction.
+    program_id: Pubkey,
+
+    /// Accounts of the instruction.
+    accounts: Vec<InputAccount>,
+
+    /// Instruction data.
+    instruction_data: Vec<u8>,
+}
+
+impl InputBuilder {
+    /// Create a builder for an instruction of the given program.
+    pub fn new(program_id: Pubkey) -> Self {
+        Self {
+            program_id,
+            accounts: Vec::new(),
+            instruction_data: Vec::new(),
+        }
+    }
+
+    /// Add an account to the instruction.
+    pub fn account(mut self, account: MockAccount) -> Self {
+        self.accounts.push(InputAccount::Account(account));
+        self
+    }
+
+    /// Add a duplicate of the account at the given position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no account at the given position or the account at
+    /// that position is itself a duplicate.
+    pub fn duplicate(mut self, index: u8) -> Self {
+        assert!(
+            matches!(
+                self.accounts.get(index as usize),
+                Some(InputAccount::Account(_))
+            ),
+            "duplicate must refer to a non-duplicate account"
+        );
+        self.accounts.push(InputAccount::Duplicate(index));
+        self
+    }
+
+    /// Set the instruction data.
+    pub fn instruction_data(mut self, data: &[u8]) -> Self {
+        self.instruction_data = data.to_vec();
+        self
+    }
+
+    /// Serialize the input buffer.
+    pub fn build(&self) -> Input {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&(self.accounts.len() as u64).to_le_bytes());
+
+        for account in &self.accounts {
+            match account {
+                InputAccount::Account(account) => {
+                    bytes.push(NON_DUP_MARKER);
+                    bytes.push(account.is_signer as u8);
+                    bytes.push(account.is_writable as u8);
+                    bytes.push(account.executable as u8);
+                    bytes.extend_from_slice(&[0; 4]);
+                    bytes.extend_from_slice(&account.key);
+                    bytes.extend_from_slice(&account.owner);
+                    bytes.extend_from_slice(&account.lamports.to_le_bytes());
+                    bytes.extend_from_slice(&(account.data.len() as u64).to_le_bytes());
+                    bytes.extend_from_slice(&account.data);
+                    bytes.resize(
+                        (bytes.len() + MAX_PERMITTED_DATA_INCREASE)
+                            .next_multiple_of(BPF_ALIGN_OF_U128),
+                        0,
+                    );
+                    bytes.extend_from_slice(&RENT_EXEMPT_RENT_EPOCH.to_le_bytes());
+                }
+                InputAccount::Duplicate(index) => {
+                    bytes.push(*index);
+                    bytes.extend_from_slice(&[0; 7]);
+                }
+            }
+        }
+
+        bytes.extend_from_slice(&(self.instruction_data.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&self.instruction_data);
+        bytes.extend_from_slice(&self.program_id);
+
+        Input::new(&bytes)
+    }
+}
+
+/// A serialized entrypoint input buffer.
+///
+/// The buffer is aligned to 8 bytes, as the input buffer provided by the runtime.
+#[derive(Clone, Debug)]
+pub struct Input {
+    /// The (aligned) buffer.
+    buffer: Vec<u64>,
+
+    /// Length of the serialized input in bytes.
+    len: usize,
+}
+
+impl Input {
+    /// Create an aligned input buffer with a copy of the given bytes.
+    fn new(bytes: &[u8]) -> Self {
+        let mut buffer = std::vec![0u64; bytes.len().div_ceil(8)];
+        // SAFETY: the buffer is large enough to hold `bytes`.
+        unsafe {
+            core::ptr::copy_nonoverlapping(
+                bytes.as_ptr(),
+                buffer.as_mut_ptr() as *mut u8,
+                bytes.len(),
+            );
+        }
+
+        Self {
+            buffer,
+            len: bytes.len(),
+        }
+    }
+
+    /// Return the serialized bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        // SAFETY: the buffer holds at least `len` bytes.
+        unsafe { core::slice::from_raw_parts(self.buffer.as_ptr() as *const u8, self.len) }
+    }
+
+    /// Return a mutable pointer to the input buffer, as expected by the program
+    /// entrypoint.
+    pub fn as_mut_ptr(&mut self) -> *mut u8 {
+        self.buffer.as_mut_ptr() as *mut u8
+    }
+
+    /// Call the given `process_instruction` function with the deserialized input,
+    /// as the [`entrypoint!`](crate::entrypoint!) does.
+    ///
+    /// Any change to the accounts is written to the input buffer, and the
+    /// non-duplicate markers repurposed by the deserialization are restored once
+    /// the function returns, so the accounts can be read back with
+    /// [`accounts`](Self::accounts). The input should only be processed once; the
+    /// input of a subsequent instruction should be built from the accounts.
+    pub fn process<F>(&mut self, process_instruction: F) -> ProgramResult
+    where
+        F: FnOnce(&Pubkey, &[AccountInfo], &[u8]) -> ProgramResult,
+    {
+        let serialized = self.serialized_accounts();
+
+        const UNINIT: core::mem::MaybeUninit<AccountInfo> =
+            core::mem::MaybeUninit::<AccountInfo>::uninit();
+        let mut accounts = [UNINIT; MAX_TX_ACCOUNTS];
+
+        // SAFETY: the buffer was serialized using the runtime format.
+        let (program_id, count, instruction_data) =
+            unsafe { deserialize::<MAX_TX_ACCOUNTS>(self.as_mut_ptr(), &mut accounts) };
+
+        // SAFETY: the first `count` accounts were initialized by `deserialize`.
+        let accounts =
+            unsafe { core::slice::from_raw_parts(accounts.as_ptr() as *const AccountInfo, count) };
+
+        let result = process_instruction(program_id, accounts, instruction_data);
+        self.restore_markers(&serialized);
+
+        result
+    }
+
+    /// Call the given `process_instruction` function with the deserialized input,
+    /// as the [`entrypoint_with_remaining!`](crate::entrypoint_with_remaining!)
+    /// does with a maximum of `MAX_ACCOUNTS` accounts.
+    ///
+    /// As with [`process`](Self::process), the non-duplicate markers are restored
+    /// once the function returns and the input should only be processed once.
+    pub fn process_with_remaining<const MAX_ACCOUNTS: usize, F>(
+        &mut self,
+        process_instruction: F,
+    ) -> ProgramResult
+    where
+        F: FnOnce(&Pubkey, &[AccountInfo], RemainingAccounts, &[u8]) -> ProgramResult,
+    {
+        let serialized = self.serialized_accounts();
+
+        const UNINIT: core::mem::MaybeUninit<AccountInfo> =
+            core::mem::MaybeUninit::<AccountInfo>::uninit();
+        let mut accounts = [UNINIT; MAX_ACCOUNTS];
+
+        // SAFETY: the buffer was serialized using the runtime format.
+        let (program_id, count, remaining, instruction_data) =
+            unsafe { deserialize_with_remaining::<MAX_ACCOUNTS>(self.as_mut_ptr(), &mut accounts) };
+
+        // SAFETY: the first `count` accounts were initialized by `deserialize`.
+        let accounts =
+            unsafe { core::slice::from_raw_parts(accounts.as_ptr() as *const AccountInfo, count) };
+
+        let result = process_instruction(program_id, accounts, remaining, instruction_data);
+        self.restore_markers(&serialized);
+
+        result
+    }
+
+    /// Return the current state of the accounts in the input buffer.
+    ///
+    /// The accounts are returned in the same order as they were added to the
+    /// builder, with duplicates resolved to the account they refer to. After the
+    /// input has been processed, this reflects the lamports, owner and data
+    /// (including its realloc'd length) written by the program, so the result
+    /// can be compared against the expected state or used to build the input of
+    /// a subsequent instruction.
+    pub fn accounts(&self) -> Vec<MockAccount> {
+        let serialized = self.serialized_accounts();
+        let mut accounts: Vec<MockAccount> = Vec::with_capacity(serialized.len());
+
+        for account in &serialized {
+            let account = match account {
+                SerializedAccount::Account(offset) => {
+                    // SAFETY: the offset points to an account header in the buffer;
+                    // the account is only read.
+                    let account_info = AccountInfo {
+                        raw: unsafe { (self.buffer.as_ptr() as *mut u8).add(*offset) }
+                            as *mut Account,
+                    };
+
+                    MockAccount {
+                        key: *account_info.key(),
+                        owner: *account_info.owner(),
+                        lamports: account_info.lamports(),
+                        // SAFETY: the data is not borrowed mutably while `self`
+                        // is borrowed.
+                        data: unsafe { account_info.borrow_data_unchecked() }.to_vec(),
+                        is_signer: account_info.is_signer(),
+                        is_writable: account_info.is_writable(),
+                        executable: account_info.executable(),
+                    }
+                }
+                SerializedAccount::Duplicate(index) => accounts[*index as usize].clone(),
+            };
+
+            accounts.push(account);
+        }
+
+        accounts
+    }
+
+    /// Return an [`InstructionContext`] for the input buffer, as the
+    /// [`lazy_entrypoint!`](crate::lazy_entrypoint!) does.
+    ///
+    /// # Safety
+    ///
+    /// The context and the accounts read from it point into the input buffer,
+    /// but are not tied to its lifetime. The caller must ensure that the input
+    /// outlives them and that the input is not accessed through other methods
+    /// while they are in use.
+    ///
+    /// Reading an account from the context replaces its non-duplicate marker in
+    /// the buffer, which is not restored, so [`accounts`](Self::accounts) cannot be
+    /// used afterwards.
+    pub unsafe fn context(&mut self) -> InstructionContext {
+        InstructionContext::new(self.as_mut_ptr())
+    }
+
+    /// Return the location of the accounts in the serialized input.
+    ///
+    /// The accounts are located by walking the buffer in the serialization format
+    /// used by the runtime. The space reserved for the data of an account depends
+    /// on its length when it was serialized, which is tracked in the account header
+    /// once the account is reallocated or closed.
+    fn serialized_accounts(&self) -> Vec<SerializedAccount> {
+        let bytes = self.as_bytes();
+        let read_u64 = |offset: usize| {
+            u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap()) as usize
+        };
+
+        let count = read_u64(0);
+        let mut accounts = Vec::with_capacity(count);
+        let mut offset = core::mem::size_of::<u64>();
+
+        for _ in 0..count {
+            if bytes[offset] == NON_DUP_MARKER {
+                accounts.push(SerializedAccount::Account(offset));
+
+                let original_data_len =
+                    u32::from_le_bytes(bytes[offset + 4..offset + 8].try_into().unwrap());
+                let data_len = if original_data_len & SET_LEN_MASK == SET_LEN_MASK {
+                    (original_data_len & GET_LEN_MASK) as usize
+                } else {
+                    // the data length field is the last one of the header
+                    read_u64(offset + core::mem::size_of::<Account>() - 8)
+                };
+
+                offset += core::mem::size_of::<Account>() + data_len + MAX_PERMITTED_DATA_INCREASE;
+                offset = offset.next_multiple_of(BPF_ALIGN_OF_U128);
+                // rent epoch
+                offset += core::mem::size_of::<u64>();
+            } else {
+                accounts.push(SerializedAccount::Duplicate(bytes[offset]));
+                offset += core::mem::size_of::<u64>();
+            }
+        }
+
+        accounts
+    }
+
+    /// Restore the non-duplicate marker of the given accounts, which the
+    /// deserialization repurposes to track borrows.
+    fn restore_markers(&mut self, accounts: &[SerializedAccount]) {
+        let buffer = self.as_mut_ptr();
+
+        for account in accounts {
+            if let SerializedAccount::Account(offset) = account {
+                // SAFETY: the offset points to an account header in the buffer.
+                unsafe { *buffer.add(*offset) = NON_DUP_MARKER };
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lazy_entrypoint::MaybeAccount, program_error::ProgramError};
+
+    fn builder() -> InputBuilder {
+        InputBuilder::new([9; 32])
+            .account(MockAccount::new([1; 32], [2; 32], 100, std::vec![1, 2, 3]).signer())
+            .account(MockAccount::new([3; 32], [9; 32], 200, Vec::new()).writable())
+            .duplicate(0)
+            .instruction_data(&[4, 5])
+    }
+
+    #[test]
+    fn test_process() {
+        let mut input = builder().build();
+
+        input
+            .process(|program_id, accounts, instruction_data| {
+                assert_eq!(program_id, &[9; 32]);
+                assert_eq!(instruction_data, &[4, 5]);
+                assert_eq!(accounts.len(), 3);
+
+                assert_eq!(accounts[0].key(), &[1; 32]);
+                assert_eq!(accounts[0].owner(), &[2; 32]);
+                assert_eq!(accounts[0].lamports(), 100);
+                assert_eq!(&*accounts[0].try_borrow_data()?, &[1, 2, 3]);
+                assert!(accounts[0].is_signer());
+                assert!(!accounts[0].is_writable());
+
+                assert_eq!(accounts[1].key(), &[3; 32]);
+                assert!(accounts[1].data_is_empty());
+                assert!(accounts[1].is_writable());
+
+                // the duplicate shares the original account
+                assert!(accounts[2] == accounts[0]);
+
+                Ok(())
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn test_context() {
+        let mut input = builder().build();
+        // SAFETY: the input outlives the context.
+        let mut context = unsafe { input.context() };
+
+        assert_eq!(context.available(), 3);
+
+        let account = context.next_account().unwrap().assume_account();
+        assert_eq!(account.key(), &[1; 32]);
+
+        let account = context.next_account().unwrap().assume_account();
+        assert_eq!(account.lamports(), 200);
+
+        assert!(matches!(
+            context.next_account(),
+            Ok(MaybeAccount::Duplicated(0))
+        ));
+
+        let (instruction_data, program_id) = context.instruction_data().unwrap();
+        assert_eq!(instruction_data, &[4, 5]);
+        assert_eq!(program_id, &[9; 32]);
+    }
+
+    #[test]
+    fn test_process_with_remaining() {
+        let mut input = builder().build();
+
+        input
+            .process_with_remaining::<1, _>(|_, accounts, mut remaining, instruction_data| {
+                assert_eq!(accounts.len(), 1);
+                assert_eq!(remaining.len(), 2);
+                assert_eq!(instruction_data, &[4, 5]);
+
+                let account = remaining.next().unwrap().assume_account();
+                assert_eq!(account.key(), &[3; 32]);
+                assert!(account.try_borrow_mut_data().is_ok());
+
+                assert!(matches!(
+                    remaining.next(),
+                    Some(MaybeAccount::Duplicated(0))
+                ));
+                assert!(remaining.next().is_none());
+
+                Ok(())
+            })
+            .unwrap();
+
+        let mut input = builder().build();
+
+        input
+            .process_with_remaining::<4, _>(|_, accounts, remaining, _| {
+                assert_eq!(accounts.len(), 3);
+                assert_eq!(remaining.len(), 0);
+                Ok(())
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn test_context_cache() {
+        let mut input = builder()
+            .account(MockAccount::new([4; 32], [9; 32], 300, Vec::new()))
+            .duplicate(1)
+            .build();
+
+        let mut context = InstructionContext::<2>::with_cache(input.as_mut_ptr());
+
+        assert_eq!(context.next_account_resolved().unwrap().key(), &[1; 32]);
+        assert!(context.cached_account(1).is_none());
+        context.skip_accounts(1).unwrap();
+        assert_eq!(context.cached_account(1).unwrap().key(), &[3; 32]);
+
+        // the duplicate of the first account is resolved
+        let account = context.next_account_resolved().unwrap();
+        assert_eq!(account.key(), &[1; 32]);
+        assert!(account == *context.cached_account(0).unwrap());
+
+        assert!(context.skip_accounts(3).is_err());
+        assert_eq!(context.remaining(), 2);
+
+        assert_eq!(context.next_account_resolved().unwrap().key(), &[4; 32]);
+        assert_eq!(context.next_account_resolved().unwrap().key(), &[3; 32]);
+        assert_eq!(
+            context.next_account_resolved().err(),
+            Some(ProgramError::NotEnoughAccountKeys)
+        );
+    }
+
+    #[test]
+    fn test_context_without_cache() {
+        let mut input = builder().build();
+        // SAFETY: the input outlives the context.
+        let mut context = unsafe { input.context() };
+
+        context.skip_accounts(2).unwrap();
+        assert!(context.cached_account(0).is_none());
+
+        // duplicates cannot be resolved without a cache
+        assert_eq!(
+            context.next_account_resolved().err(),
+            Some(ProgramError::InvalidArgument)
+        );
+
+        let mut input = builder().build();
+        // SAFETY: the input outlives the context.
+        let mut context = unsafe { input.context() };
+
+        let (instruction_data, program_id) = context.skip_to_instruction_data();
+        assert_eq!(instruction_data, &[4, 5]);
+        assert_eq!(program_id, &[9; 32]);
+    }
+
+    #[test]
+    fn test_accounts() {
+        let mut input = builder().build();
+        assert_eq!(
+            input.accounts(),
+            [
+                MockAccount::new([1; 32], [2; 32], 100, std::vec![1, 2, 3]).signer(),
+                MockAccount::new([3; 32], [9; 32], 200, Vec::new()).writable(),
+                MockAccount::new([1; 32], [2; 32], 100, std::vec![1, 2, 3]).signer(),
+            ]
+        );
+
+        input
+            .process(|_, accounts, _| {
+                accounts[0].close()?;
+
+                *accounts[1].try_borrow_mut_lamports()? += 100;
+                accounts[1].assign(&[7; 32]);
+                accounts[1].realloc(4, true)?;
+                accounts[1]
+                    .try_borrow_mut_data()?
+                    .copy_from_slice(&[1, 2, 3, 4]);
+
+                Ok(())
+            })
+            .unwrap();
+
+        let accounts = input.accounts();
+
+        // the closed account is zeroed, but its data remains in the buffer
+        assert_eq!(accounts[0].lamports, 0);
+        assert_eq!(accounts[0].owner, [0; 32]);
+        assert!(accounts[0].data.is_empty());
+
+        assert_eq!(
+            accounts[1],
+            MockAccount::new([3; 32], [7; 32], 300, std::vec![1, 2, 3, 4]).writable()
+        );
+        assert_eq!(accounts[2], accounts[0]);
+    }
+
+    #[test]
+    fn test_accounts_after_realloc_and_close() {
+        let mut input = builder()
+            .account(MockAccount::new([4; 32], [9; 32], 300, std::vec![5; 8]).writable())
+            .duplicate(1)
+            .build();
+
+        input
+            .process(|_, accounts, _| {
+                accounts[1].realloc(16, true)?;
+                accounts[3].close()?;
+                Ok(())
+            })
+            .unwrap();
+
+        // the accounts are located using their original data length
+        let accounts = input.accounts();
+
+        assert_eq!(accounts.len(), 5);
+        assert_eq!(accounts[1].data, [0; 16]);
+        assert_eq!(accounts[2], accounts[0]);
+        assert_eq!(accounts[3].key, [4; 32]);
+        assert!(accounts[3].data.is_empty());
+        assert_eq!(accounts[4], accounts[1]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_duplicate() {
+        builder().duplicate(2);
+    }
+}
@@ -0,0 +1,17 @@
+//! Utilities to unit test programs off-chain.
+//!
+//! These utilities are only available with the `std` feature. They allow a
+//! program's `process_instruction` to be called directly from `cargo test` by
+//! producing the same input buffer that the runtime provides to the program
+//! entrypoint, and by replacing the syscalls with stubs that record the logs,
+//! cross-program invocations and return data of the program.
+
+mod input;
+mod runtime;
+mod stubs;
+
+pub use input::*;
+pub use runtime::*;
+#[cfg(not(target_os = "solana"))]
+pub(crate) use stubs::with_syscall_stubs;
+pub use stubs::{set_syscall_stubs, SyscallStubs};
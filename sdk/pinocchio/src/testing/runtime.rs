@@ -0,0 +1,543 @@
+//! Syscall stubs recording the effects of a program.
+
+use std::{
+    any::{Any, TypeId},
+    boxed::Box,
+    cell::RefCell,
+    collections::HashMap,
+    format,
+    rc::Rc,
+    string::String,
+    vec::Vec,
+};
+
+use super::SyscallStubs;
+use crate::{
+    instruction::{Account, Instruction, Signer},
+    program_error::UNSUPPORTED_SYSVAR,
+    pubkey::Pubkey,
+    sysvars::{
+        clock::Clock, epoch_rewards::EpochRewards, epoch_schedule::EpochSchedule, fees::Fees,
+        last_restart_slot::LastRestartSlot, rent::Rent, Sysvar, OFFSET_LENGTH_EXCEEDS_SYSVAR,
+        SYSVAR_NOT_FOUND,
+    },
+    ProgramResult, SUCCESS,
+};
+
+/// A cross-program invocation recorded by the [`MockRuntime`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InvokedInstruction {
+    /// Public key of the invoked program.
+    pub program_id: Pubkey,
+
+    /// Accounts of the instruction.
+    pub accounts: Vec<InvokedAccountMeta>,
+
+    /// Data of the instruction.
+    pub data: Vec<u8>,
+
+    /// Seeds of each PDA signer.
+    pub signers_seeds: Vec<Vec<Vec<u8>>>,
+}
+
+/// An account of a recorded cross-program invocation.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InvokedAccountMeta {
+    /// Public key of the account.
+    pub pubkey: Pubkey,
+
+    /// Indicates whether the account is writable or not.
+    pub is_writable: bool,
+
+    /// Indicates whether the account signed the instruction or not.
+    pub is_signer: bool,
+}
+
+/// Handler called for each cross-program invocation.
+type InvokeHandler = Rc<dyn Fn(&InvokedInstruction) -> ProgramResult>;
+
+/// State shared by the clones of a [`MockRuntime`].
+#[derive(Default)]
+struct State {
+    /// Program id of the running program.
+    program_id: Pubkey,
+
+    /// Messages logged by the program.
+    logs: Vec<String>,
+
+    /// Slices logged with `sol_log_data`.
+    data_logs: Vec<Vec<Vec<u8>>>,
+
+    /// Cross-program invocations, in invocation order.
+    invocations: Vec<InvokedInstruction>,
+
+    /// Handler simulating the invoked programs.
+    invoke_handler: Option<InvokeHandler>,
+
+    /// Current return data and the program that set it.
+    return_data: Option<(Pubkey, Vec<u8>)>,
+
+    /// Sysvars loaded with `Sysvar::get`, by type.
+    sysvars: HashMap<TypeId, Box<dyn Any>>,
+
+    /// Sysvar account data read with `get_sysvar`, by sysvar id.
+    sysvars_data: HashMap<Pubkey, Vec<u8>>,
+}
+
+/// Syscall stubs that record the logs, cross-program invocations and return
+/// data of a program, and provide the sysvars it loads.
+///
+/// A `MockRuntime` is a handle to a shared state: once a clone is installed with
+/// [`MockRuntime::install`], the original can be used to configure the runtime
+/// and inspect what the program did.
+///
+/// ```
+/// use pinocchio::{msg, sysvars::{clock::Clock, Sysvar}, testing::MockRuntime};
+///
+/// let runtime = MockRuntime::new([1; 32]);
+/// runtime.set_sysvar(Clock {
+///     slot: 42,
+///     ..Default::default()
+/// });
+/// runtime.install();
+///
+/// msg!("hello");
+///
+/// assert_eq!(Clock::get().unwrap().slot, 42);
+/// assert_eq!(runtime.logs(), ["hello"]);
+/// ```
+#[derive(Clone, Default)]
+pub struct MockRuntime {
+    /// The shared state.
+    state: Rc<RefCell<State>>,
+}
+
+impl MockRuntime {
+    /// Create a runtime for the program with the given id.
+    ///
+    /// The program id is reported as the owner of the return data set by the
+    /// program.
+    pub fn new(program_id: Pubkey) -> Self {
+        let runtime = Self::default();
+        runtime.state.borrow_mut().program_id = program_id;
+        runtime
+    }
+
+    /// Install the runtime as the syscall stubs of the current thread,
+    /// returning the previously installed stubs.
+    pub fn install(&self) -> Rc<dyn SyscallStubs> {
+        super::set_syscall_stubs(Rc::new(self.clone()))
+    }
+
+    /// Return the messages logged by the program.
+    ///
+    /// Values logged with `sol_log_64` are formatted in hexadecimal and pubkeys
+    /// are formatted in base58.
+    pub fn logs(&self) -> Vec<String> {
+        self.state.borrow().logs.clone()
+    }
+
+    /// Return the slices logged with `sol_log_data`.
+    pub fn data_logs(&self) -> Vec<Vec<Vec<u8>>> {
+        self.state.borrow().data_logs.clone()
+    }
+
+    /// Return the cross-program invocations of the program.
+    pub fn invocations(&self) -> Vec<InvokedInstruction> {
+        self.state.borrow().invocations.clone()
+    }
+
+    /// Set the handler called for each cross-program invocation.
+    ///
+    /// The handler simulates the invoked program: its result is returned to the
+    /// program, and it can set return data using [`MockRuntime::set_return_data`].
+    /// Without a handler, all invocations succeed.
+    pub fn set_invoke_handler<F>(&self, handler: F)
+    where
+        F: Fn(&InvokedInstruction) -> ProgramResult + 'static,
+    {
+        self.state.borrow_mut().invoke_handler = Some(Rc::new(handler));
+    }
+
+    /// Return the current return data and the program that set it.
+    pub fn return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        self.state.borrow().return_data.clone()
+    }
+
+    /// Set the return data as if it was set by the given program.
+    pub fn set_return_data(&self, program_id: Pubkey, data: &[u8]) {
+        self.state.borrow_mut().return_data =
+            (!data.is_empty()).then(|| (program_id, data.to_vec()));
+    }
+
+    /// Set the value of a sysvar loaded with [`Sysvar::get`].
+    pub fn set_sysvar<T: Sysvar + Clone + 'static>(&self, sysvar: T) {
+        self.state
+            .borrow_mut()
+            .sysvars
+            .insert(TypeId::of::<T>(), Box::new(sysvar));
+    }
+
+    /// Set the account data of a sysvar read with
+    /// [`get_sysvar`](crate::sysvars::get_sysvar).
+    pub fn set_sysvar_data(&self, sysvar_id: Pubkey, data: &[u8]) {
+        self.state
+            .borrow_mut()
+            .sysvars_data
+            .insert(sysvar_id, data.to_vec());
+    }
+
+    /// Write the sysvar of type `T` to `var_addr`.
+    ///
+    /// `var_addr` must point to a `T`, as provided by [`Sysvar::get`].
+    fn load_sysvar<T: Clone + 'static>(&self, var_addr: *mut u8) -> u64 {
+        let state = self.state.borrow();
+
+        match state
+            .sysvars
+            .get(&TypeId::of::<T>())
+            .and_then(|sysvar| sysvar.downcast_ref::<T>())
+        {
+            Some(sysvar) => {
+                // SAFETY: `var_addr` points to a `T`.
+                unsafe { (var_addr as *mut T).write(sysvar.clone()) };
+                SUCCESS
+            }
+            None => UNSUPPORTED_SYSVAR,
+        }
+    }
+}
+
+impl SyscallStubs for MockRuntime {
+    fn sol_log(&self, message: &str) {
+        self.state.borrow_mut().logs.push(message.into());
+    }
+
+    fn sol_log_64(&self, arg1: u64, arg2: u64, arg3: u64, arg4: u64, arg5: u64) {
+        self.state.borrow_mut().logs.push(format!(
+            "{:#x}, {:#x}, {:#x}, {:#x}, {:#x}",
+            arg1, arg2, arg3, arg4, arg5
+        ));
+    }
+
+    fn sol_log_data(&self, data: &[&[u8]]) {
+        self.state
+            .borrow_mut()
+            .data_logs
+            .push(data.iter().map(|slice| slice.to_vec()).collect());
+    }
+
+    fn sol_log_pubkey(&self, pubkey: &Pubkey) {
+        self.state.borrow_mut().logs.push(bs58(pubkey));
+    }
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        _accounts: &[Account],
+        signers_seeds: &[Signer],
+    ) -> ProgramResult {
+        let invoked = InvokedInstruction {
+            program_id: *instruction.program_id,
+            accounts: instruction
+                .accounts
+                .iter()
+                .map(|meta| InvokedAccountMeta {
+                    pubkey: *meta.pubkey,
+                    is_writable: meta.is_writable,
+                    is_signer: meta.is_signer,
+                })
+                .collect(),
+            data: instruction.data.to_vec(),
+            signers_seeds: signers_seeds
+                .iter()
+                .map(|signer| signer.iter().map(|seed| seed.to_vec()).collect())
+                .collect(),
+        };
+
+        // Return data is cleared before every invocation.
+        let handler = {
+            let mut state = self.state.borrow_mut();
+            state.return_data = None;
+            state.invocations.push(invoked.clone());
+            state.invoke_handler.clone()
+        };
+
+        match handler {
+            Some(handler) => handler(&invoked),
+            None => Ok(()),
+        }
+    }
+
+    fn sol_set_return_data(&self, data: &[u8]) {
+        let program_id = self.state.borrow().program_id;
+        self.set_return_data(program_id, data);
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        self.return_data()
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.load_sysvar::<Clock>(var_addr)
+    }
+
+    fn sol_get_epoch_rewards_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.load_sysvar::<EpochRewards>(var_addr)
+    }
+
+    fn sol_get_epoch_schedule_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.load_sysvar::<EpochSchedule>(var_addr)
+    }
+
+    fn sol_get_fees_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.load_sysvar::<Fees>(var_addr)
+    }
+
+    fn sol_get_last_restart_slot(&self, var_addr: *mut u8) -> u64 {
+        self.load_sysvar::<LastRestartSlot>(var_addr)
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.load_sysvar::<Rent>(var_addr)
+    }
+
+    fn sol_get_sysvar(&self, sysvar_id: &Pubkey, dst: &mut [u8], offset: u64) -> u64 {
+        let state = self.state.borrow();
+
+        let Some(data) = state.sysvars_data.get(sysvar_id) else {
+            return SYSVAR_NOT_FOUND;
+        };
+
+        match (offset as usize)
+            .checked_add(dst.len())
+            .and_then(|end| data.get(offset as usize..end))
+        {
+            Some(source) => {
+                dst.copy_from_slice(source);
+                SUCCESS
+            }
+            None => OFFSET_LENGTH_EXCEEDS_SYSVAR,
+        }
+    }
+}
+
+/// Encode the pubkey in base58, as it is logged by the runtime.
+fn bs58(pubkey: &Pubkey) -> String {
+    const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+    // Base58 digits in little-endian order.
+    let mut digits = Vec::<u8>::with_capacity(44);
+
+    for byte in pubkey {
+        let mut carry = *byte as u32;
+
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+
+    // Each leading zero byte is encoded as a '1'.
+    let zeros = pubkey.iter().take_while(|byte| **byte == 0).count();
+
+    core::iter::repeat('1')
+        .take(zeros)
+        .chain(
+            digits
+                .iter()
+                .rev()
+                .map(|digit| ALPHABET[*digit as usize] as char),
+        )
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        instruction::AccountMeta,
+        log::{sol_log_64, sol_log_data},
+        msg,
+        program::{get_return_data, invoke, invoke_signed_unchecked, set_return_data},
+        program_error::ProgramError,
+        pubkey::log,
+        signer,
+        sysvars::{get_sysvar, slot_hashes},
+    };
+
+    #[test]
+    fn test_logs() {
+        let runtime = MockRuntime::new([1; 32]);
+        runtime.install();
+
+        msg!("hello");
+        sol_log_64(1, 2, 3, 4, 255);
+        log(&slot_hashes::ID);
+        sol_log_data(&[&[1, 2], &[3]]);
+
+        assert_eq!(
+            runtime.logs(),
+            [
+                "hello",
+                "0x1, 0x2, 0x3, 0x4, 0xff",
+                "SysvarS1otHashes111111111111111111111111111",
+            ]
+        );
+        assert_eq!(runtime.data_logs(), [[std::vec![1, 2], std::vec![3]]]);
+    }
+
+    #[test]
+    fn test_invoke() {
+        let runtime = MockRuntime::new([1; 32]);
+        runtime.install();
+
+        let handler = runtime.clone();
+        runtime.set_invoke_handler(move |instruction| match instruction.data[0] {
+            0 => {
+                handler.set_return_data(instruction.program_id, &[7, 8]);
+                Ok(())
+            }
+            _ => Err(ProgramError::Custom(3)),
+        });
+
+        let accounts = [AccountMeta::writable_signer(&[2; 32])];
+        let instruction = Instruction {
+            program_id: &[9; 32],
+            data: &[0],
+            accounts: &accounts,
+        };
+
+        assert_eq!(
+            unsafe { invoke_signed_unchecked(&instruction, &[], &[signer!(b"seed", &[255])]) },
+            Ok(())
+        );
+
+        let return_data = get_return_data().unwrap();
+        assert_eq!(return_data.program_id(), &[9; 32]);
+        assert_eq!(return_data.as_slice(), &[7, 8]);
+
+        let instruction = Instruction {
+            program_id: &[9; 32],
+            data: &[1],
+            accounts: &[],
+        };
+
+        assert_eq!(invoke(&instruction, &[]), Err(ProgramError::Custom(3)));
+        // return data is cleared before each invocation
+        assert!(get_return_data().is_none());
+
+        assert_eq!(
+            runtime.invocations(),
+            [
+                InvokedInstruction {
+                    program_id: [9; 32],
+                    accounts: std::vec![InvokedAccountMeta {
+                        pubkey: [2; 32],
+                        is_writable: true,
+                        is_signer: true,
+                    }],
+                    data: std::vec![0],
+                    signers_seeds: std::vec![std::vec![b"seed".to_vec(), std::vec![255]]],
+                },
+                InvokedInstruction {
+                    program_id: [9; 32],
+                    data: std::vec![1],
+                    ..Default::default()
+                },
+            ]
+        );
+
+        set_return_data(&[1, 2, 3]);
+        assert_eq!(runtime.return_data(), Some(([1; 32], std::vec![1, 2, 3])));
+    }
+
+    #[test]
+    fn test_sysvars() {
+        let runtime = MockRuntime::default();
+        runtime.install();
+
+        assert!(matches!(Clock::get(), Err(ProgramError::UnsupportedSysvar)));
+
+        runtime.set_sysvar(Clock {
+            slot: 10,
+            unix_timestamp: 1_700_000_000,
+            ..Default::default()
+        });
+
+        let clock = Clock::get().unwrap();
+        assert_eq!(clock.slot, 10);
+        assert_eq!(clock.unix_timestamp, 1_700_000_000);
+
+        let mut data = std::vec![0u8; 8 + 2 * 40];
+        data[..8].copy_from_slice(&2u64.to_le_bytes());
+        data[48..56].copy_from_slice(&5u64.to_le_bytes());
+        data[56..].copy_from_slice(&[5; 32]);
+        runtime.set_sysvar_data(slot_hashes::ID, &data);
+
+        assert_eq!(slot_hashes::get_len(), Ok(2));
+        assert_eq!(
+            slot_hashes::get_entry(1),
+            Ok(slot_hashes::SlotHash {
+                slot: 5,
+                hash: [5; 32],
+            })
+        );
+        assert_eq!(
+            slot_hashes::get_entry(2),
+            Err(ProgramError::InvalidArgument)
+        );
+
+        let mut dst = [0u8; 8];
+        assert_eq!(
+            get_sysvar(&mut dst, &slot_hashes::ID, 90, 8),
+            Err(ProgramError::InvalidArgument)
+        );
+    }
+
+    #[test]
+    fn test_install_from_invoke_handler() {
+        let runtime = MockRuntime::default();
+        runtime.install();
+
+        let nested = MockRuntime::default();
+        let handler = nested.clone();
+        runtime.set_invoke_handler(move |_| {
+            handler.install();
+            msg!("nested");
+            Ok(())
+        });
+
+        let instruction = Instruction {
+            program_id: &[9; 32],
+            data: &[],
+            accounts: &[],
+        };
+
+        assert_eq!(invoke(&instruction, &[]), Ok(()));
+        msg!("after");
+
+        assert!(runtime.logs().is_empty());
+        assert_eq!(nested.logs(), ["nested", "after"]);
+    }
+
+    #[test]
+    fn test_sysvar_without_stub() {
+        #[derive(Default)]
+        struct Unknown;
+
+        impl Sysvar for Unknown {
+            crate::impl_sysvar_get!(sol_get_unknown_sysvar);
+        }
+
+        MockRuntime::default().install();
+
+        assert!(Unknown::get().is_err());
+    }
+}
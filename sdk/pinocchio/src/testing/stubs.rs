@@ -0,0 +1,140 @@
+//! Pluggable implementation of syscalls for host targets.
+//!
+//! Off-chain, syscalls are forwarded to the [`SyscallStubs`] installed for the
+//! current thread. The default stubs preserve the behavior of the SDK on host
+//! targets: logs are discarded, CPIs succeed without any effect and sysvars are
+//! not available.
+
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    instruction::{Account, Instruction, Signer},
+    program_error::UNSUPPORTED_SYSVAR,
+    pubkey::Pubkey,
+    sysvars::SYSVAR_NOT_FOUND,
+    ProgramResult,
+};
+
+std::thread_local! {
+    /// Syscall stubs installed for the current thread.
+    static SYSCALL_STUBS: RefCell<Rc<dyn SyscallStubs>> = RefCell::new(Rc::new(DefaultSyscallStubs));
+}
+
+/// Install the syscall stubs for the current thread, returning the previously
+/// installed stubs.
+///
+/// Since the stubs are installed per thread, tests running in parallel do not
+/// interfere with each other. The stubs can be replaced while a syscall is being
+/// executed (e.g., by an invoke handler), in which case the new stubs are used
+/// by the following syscalls.
+pub fn set_syscall_stubs(stubs: Rc<dyn SyscallStubs>) -> Rc<dyn SyscallStubs> {
+    SYSCALL_STUBS.with(|current| current.replace(stubs))
+}
+
+/// Call `f` with the syscall stubs installed for the current thread.
+///
+/// The registry is not borrowed while `f` runs, so syscalls executed by `f`
+/// can also install new stubs.
+#[cfg(not(target_os = "solana"))]
+#[inline(always)]
+pub(crate) fn with_syscall_stubs<R>(f: impl FnOnce(&dyn SyscallStubs) -> R) -> R {
+    let stubs = SYSCALL_STUBS.with(|stubs| stubs.borrow().clone());
+    f(stubs.as_ref())
+}
+
+/// Implementation of the syscalls used on host targets.
+///
+/// All methods have a default implementation, so an implementation only needs
+/// to override the syscalls it is interested in.
+pub trait SyscallStubs {
+    /// Print a string to the log.
+    fn sol_log(&self, message: &str) {
+        let _ = message;
+    }
+
+    /// Print 64-bit values to the log.
+    fn sol_log_64(&self, arg1: u64, arg2: u64, arg3: u64, arg4: u64, arg5: u64) {
+        let _ = (arg1, arg2, arg3, arg4, arg5);
+    }
+
+    /// Print some slices to the log.
+    fn sol_log_data(&self, data: &[&[u8]]) {
+        let _ = data;
+    }
+
+    /// Print the remaining compute units available to the program.
+    fn sol_log_compute_units(&self) {}
+
+    /// Print a pubkey to the log.
+    fn sol_log_pubkey(&self, pubkey: &Pubkey) {
+        let _ = pubkey;
+    }
+
+    /// Invoke a cross-program instruction.
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        accounts: &[Account],
+        signers_seeds: &[Signer],
+    ) -> ProgramResult {
+        let _ = (instruction, accounts, signers_seeds);
+        Ok(())
+    }
+
+    /// Set the return data of the running program.
+    fn sol_set_return_data(&self, data: &[u8]) {
+        let _ = data;
+    }
+
+    /// Get the return data and the program that set it.
+    fn sol_get_return_data(&self) -> Option<(Pubkey, std::vec::Vec<u8>)> {
+        None
+    }
+
+    /// Load the clock sysvar into `var_addr`.
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let _ = var_addr;
+        UNSUPPORTED_SYSVAR
+    }
+
+    /// Load the epoch rewards sysvar into `var_addr`.
+    fn sol_get_epoch_rewards_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let _ = var_addr;
+        UNSUPPORTED_SYSVAR
+    }
+
+    /// Load the epoch schedule sysvar into `var_addr`.
+    fn sol_get_epoch_schedule_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let _ = var_addr;
+        UNSUPPORTED_SYSVAR
+    }
+
+    /// Load the fees sysvar into `var_addr`.
+    fn sol_get_fees_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let _ = var_addr;
+        UNSUPPORTED_SYSVAR
+    }
+
+    /// Load the last restart slot sysvar into `var_addr`.
+    fn sol_get_last_restart_slot(&self, var_addr: *mut u8) -> u64 {
+        let _ = var_addr;
+        UNSUPPORTED_SYSVAR
+    }
+
+    /// Load the rent sysvar into `var_addr`.
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let _ = var_addr;
+        UNSUPPORTED_SYSVAR
+    }
+
+    /// Copy `dst.len()` bytes of the sysvar data, starting at `offset`, into `dst`.
+    fn sol_get_sysvar(&self, sysvar_id: &Pubkey, dst: &mut [u8], offset: u64) -> u64 {
+        let _ = (sysvar_id, dst, offset);
+        SYSVAR_NOT_FOUND
+    }
+}
+
+/// Syscall stubs used when no other stubs are installed.
+struct DefaultSyscallStubs;
+
+impl SyscallStubs for DefaultSyscallStubs {}
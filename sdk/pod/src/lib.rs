@@ -0,0 +1,496 @@
+//! Derive macro for zero-copy account state structs.
+//!
+//! Account state is usually represented by `#[repr(C)]` structs that are read directly
+//! from the account data. To avoid alignment requirements, integer fields are stored as
+//! little-endian byte arrays and optional values use the `COption` layout, a `[u8; 4]`
+//! tag followed by the value. The [`Pod`] derive checks the layout of such structs at
+//! compile time and generates typed accessors for their fields.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, Data, DeriveInput, Error, Expr, ExprLit, Field, Fields, GenericArgument,
+    Ident, Lit, Meta, NestedMeta, PathArguments, Type,
+};
+
+/// Integer types that can be read from and written to little-endian byte arrays.
+const INTEGER_TYPES: &[&str] = &[
+    "u8", "u16", "u32", "u64", "u128", "i8", "i16", "i32", "i64", "i128",
+];
+
+/// Accessors to generate for a field.
+enum Accessor<'a> {
+    /// Integer stored as a little-endian byte array.
+    Integer(Ident),
+
+    /// Optional value preceded by a `[u8; 4]` flag field.
+    Option(Ident),
+
+    /// Optional value stored in a `COption` field.
+    COption(&'a Type),
+}
+
+/// Derive zero-copy layout checks and typed accessors for an account state struct.
+///
+/// The struct must be `#[repr(C)]` with named fields. The derive asserts at compile
+/// time that the struct has an alignment of 1 and no padding, so it can be read in
+/// place from any offset of the account data. This is the case when all fields are
+/// byte arrays (or structs with the same property).
+///
+/// Fields can be annotated with a `#[pod(...)]` attribute to generate accessors:
+///
+/// - `#[pod(u64)]` (or any other integer type) on a `[u8; N]` field generates a getter
+///   returning the little-endian value of the field and a `set_*` setter.
+/// - `#[pod(option)]` on a `COption<T>` field generates a getter returning an `Option` of
+///   a reference to the value and a `set_*` setter. The same accessors are generated for
+///   a field preceded by a `[u8; 4]` field with the same name and a `_flag` suffix, which
+///   is read with the same semantics as a `COption`: the value is present only when the
+///   flag is `[1, 0, 0, 0]`. The value is reset to its default (e.g., zeroed for a
+///   `Pubkey`) when set to `None`.
+///
+/// # Examples
+///
+/// ```
+/// use pinocchio::{coption::COption, pubkey::Pubkey};
+/// use pinocchio_pod::Pod;
+///
+/// #[derive(Pod)]
+/// #[repr(C)]
+/// pub struct Vault {
+///     #[pod(u64)]
+///     amount: [u8; 8],
+///
+///     #[pod(option)]
+///     delegate: COption<Pubkey>,
+///
+///     authority_flag: [u8; 4],
+///
+///     #[pod(option)]
+///     authority: Pubkey,
+/// }
+///
+/// let mut vault = Vault {
+///     amount: [0; 8],
+///     delegate: COption::none(),
+///     authority_flag: [0; 4],
+///     authority: [0; 32],
+/// };
+///
+/// vault.set_amount(1_000);
+/// assert_eq!(vault.amount(), 1_000);
+/// assert_eq!(vault.amount, 1_000u64.to_le_bytes());
+///
+/// assert_eq!(vault.authority(), None);
+/// vault.set_authority(Some(&[1; 32]));
+/// assert_eq!(vault.authority(), Some(&[1; 32]));
+/// assert_eq!(vault.authority_flag, [1, 0, 0, 0]);
+///
+/// vault.set_delegate(Some(&[2; 32]));
+/// assert_eq!(vault.delegate(), Some(&[2; 32]));
+/// assert!(vault.delegate.is_some());
+/// ```
+///
+/// Structs with padding or alignment requirements are rejected:
+///
+/// ```compile_fail
+/// use pinocchio_pod::Pod;
+///
+/// #[derive(Pod)]
+/// #[repr(C)]
+/// pub struct Vault {
+///     amount: u64,
+/// }
+/// ```
+#[proc_macro_derive(Pod, attributes(pod))]
+pub fn derive_pod(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match expand(&input) {
+        Ok(expanded) => expanded.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "`Pod` cannot be derived for generic structs",
+        ));
+    }
+
+    if !is_repr_c(input) {
+        return Err(Error::new_spanned(name, "`Pod` requires `#[repr(C)]`"));
+    }
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new_spanned(
+                    name,
+                    "`Pod` can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                name,
+                "`Pod` can only be derived for structs",
+            ))
+        }
+    };
+
+    let types = fields.iter().map(|field| &field.ty);
+    let mut accessors = Vec::new();
+
+    for field in fields {
+        let Some(accessor) = parse_accessor(field)? else {
+            continue;
+        };
+
+        // named fields always have an ident
+        let field_name = field.ident.as_ref().unwrap();
+        let setter = format_ident!("set_{}", field_name);
+        let ty = &field.ty;
+
+        accessors.push(match accessor {
+            Accessor::Integer(integer) => {
+                let getter_doc = format!("Return the value of the `{}` field.", field_name);
+                let setter_doc = format!("Set the value of the `{}` field.", field_name);
+
+                quote! {
+                    #[doc = #getter_doc]
+                    #[inline(always)]
+                    pub fn #field_name(&self) -> #integer {
+                        #integer::from_le_bytes(self.#field_name)
+                    }
+
+                    #[doc = #setter_doc]
+                    #[inline(always)]
+                    pub fn #setter(&mut self, value: #integer) {
+                        self.#field_name = value.to_le_bytes();
+                    }
+                }
+            }
+            Accessor::COption(value) => {
+                let getter_doc = format!("Return the `{}` field, if present.", field_name);
+                let setter_doc =
+                    format!("Set the `{}` field, resetting it when `None`.", field_name);
+
+                quote! {
+                    #[doc = #getter_doc]
+                    #[inline(always)]
+                    pub fn #field_name(&self) -> Option<&#value> {
+                        self.#field_name.as_ref()
+                    }
+
+                    #[doc = #setter_doc]
+                    #[inline(always)]
+                    pub fn #setter(&mut self, value: Option<&#value>) {
+                        self.#field_name.set(value.copied());
+                    }
+                }
+            }
+            Accessor::Option(flag) => {
+                match fields
+                    .iter()
+                    .find(|field| field.ident.as_ref() == Some(&flag))
+                {
+                    Some(flag_field) if is_flag_type(&flag_field.ty) => (),
+                    Some(flag_field) => {
+                        return Err(Error::new_spanned(
+                            &flag_field.ty,
+                            format!("`{}` must be a `[u8; 4]` field", flag),
+                        ))
+                    }
+                    None => {
+                        return Err(Error::new_spanned(
+                            field_name,
+                            format!("`#[pod(option)]` requires a `{}: [u8; 4]` field", flag),
+                        ))
+                    }
+                }
+
+                let getter_doc = format!("Return the `{}` field, if present.", field_name);
+                let setter_doc =
+                    format!("Set the `{}` field, resetting it when `None`.", field_name);
+
+                quote! {
+                    #[doc = #getter_doc]
+                    #[inline(always)]
+                    pub fn #field_name(&self) -> Option<&#ty> {
+                        if self.#flag == [1, 0, 0, 0] {
+                            Some(&self.#field_name)
+                        } else {
+                            None
+                        }
+                    }
+
+                    #[doc = #setter_doc]
+                    #[inline(always)]
+                    pub fn #setter(&mut self, value: Option<&#ty>) {
+                        match value {
+                            Some(value) => {
+                                self.#flag = [1, 0, 0, 0];
+                                self.#field_name = *value;
+                            }
+                            None => {
+                                self.#flag = [0; 4];
+                                self.#field_name = Default::default();
+                            }
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    let align_message = format!("`{}` must have an alignment of 1", name);
+    let padding_message = format!("`{}` must not have padding", name);
+
+    Ok(quote! {
+        const _: () = {
+            assert!(core::mem::align_of::<#name>() == 1, #align_message);
+            assert!(
+                core::mem::size_of::<#name>() == 0 #(+ core::mem::size_of::<#types>())*,
+                #padding_message
+            );
+        };
+
+        impl #name {
+            #(#accessors)*
+        }
+    })
+}
+
+/// Check whether the struct has a `#[repr(C)]` attribute.
+fn is_repr_c(input: &DeriveInput) -> bool {
+    input.attrs.iter().any(|attr| {
+        attr.path.is_ident("repr")
+            && matches!(
+                attr.parse_meta(),
+                Ok(Meta::List(list)) if list.nested.iter().any(|nested| matches!(
+                    nested,
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("C")
+                ))
+            )
+    })
+}
+
+/// Return the type of the value of a `COption<T>` type.
+fn coption_value(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+
+    let segment = path.path.segments.last()?;
+
+    if segment.ident != "COption" {
+        return None;
+    }
+
+    let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return None;
+    };
+
+    match (arguments.args.first(), arguments.args.len()) {
+        (Some(GenericArgument::Type(value)), 1) => Some(value),
+        _ => None,
+    }
+}
+
+/// Check whether the type is `[u8; 4]`.
+fn is_flag_type(ty: &Type) -> bool {
+    let Type::Array(array) = ty else {
+        return false;
+    };
+
+    matches!(&*array.elem, Type::Path(path) if path.qself.is_none() && path.path.is_ident("u8"))
+        && matches!(
+            &array.len,
+            Expr::Lit(ExprLit { lit: Lit::Int(len), .. }) if len.base10_parse::<usize>().ok() == Some(4)
+        )
+}
+
+/// Parse the `#[pod(...)]` attribute of a field.
+fn parse_accessor(field: &Field) -> syn::Result<Option<Accessor<'_>>> {
+    let Some(attr) = field.attrs.iter().find(|attr| attr.path.is_ident("pod")) else {
+        return Ok(None);
+    };
+
+    let invalid = || {
+        Error::new_spanned(
+            attr,
+            "expected `#[pod(option)]` or `#[pod(<integer type>)]`",
+        )
+    };
+
+    let Meta::List(list) = attr.parse_meta()? else {
+        return Err(invalid());
+    };
+
+    let mut nested = list.nested.iter();
+
+    let (Some(NestedMeta::Meta(Meta::Path(path))), None) = (nested.next(), nested.next()) else {
+        return Err(invalid());
+    };
+
+    let Some(ident) = path.get_ident() else {
+        return Err(invalid());
+    };
+
+    if ident == "option" {
+        if let Some(value) = coption_value(&field.ty) {
+            return Ok(Some(Accessor::COption(value)));
+        }
+
+        // named fields always have an ident
+        let flag = format_ident!("{}_flag", field.ident.as_ref().unwrap());
+
+        return Ok(Some(Accessor::Option(flag)));
+    }
+
+    if INTEGER_TYPES.iter().any(|integer| ident == integer) {
+        if !matches!(field.ty, Type::Array(_)) {
+            return Err(Error::new_spanned(
+                &field.ty,
+                "integer accessors require a byte array field",
+            ));
+        }
+
+        return Ok(Some(Accessor::Integer(ident.clone())));
+    }
+
+    Err(invalid())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand_error(input: &str) -> String {
+        let input = syn::parse_str::<DeriveInput>(input).unwrap();
+        expand(&input).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn test_expand() {
+        let input = syn::parse_str::<DeriveInput>(
+            "#[repr(C)]
+            struct Vault {
+                #[pod(u64)]
+                amount: [u8; 8],
+                authority_flag: [u8; 4],
+                #[pod(option)]
+                authority: [u8; 32],
+            }",
+        )
+        .unwrap();
+
+        let expanded = expand(&input).unwrap().to_string();
+
+        assert!(expanded.contains("pub fn amount"));
+        assert!(expanded.contains("pub fn set_authority"));
+    }
+
+    #[test]
+    fn test_expand_coption() {
+        let input = syn::parse_str::<DeriveInput>(
+            "#[repr(C)]
+            struct Vault {
+                #[pod(option)]
+                delegate: pinocchio::coption::COption<Pubkey>,
+            }",
+        )
+        .unwrap();
+
+        let expanded = expand(&input).unwrap().to_string();
+
+        assert!(expanded.contains("Option < & Pubkey >"));
+        assert!(!expanded.contains("delegate_flag"));
+    }
+
+    #[test]
+    fn test_missing_flag() {
+        assert_eq!(
+            expand_error(
+                "#[repr(C)]
+                struct Vault {
+                    #[pod(option)]
+                    authority: [u8; 32],
+                }"
+            ),
+            "`#[pod(option)]` requires a `authority_flag: [u8; 4]` field"
+        );
+    }
+
+    #[test]
+    fn test_invalid_flag() {
+        for flag in ["[u8; 1]", "[u16; 4]", "u32"] {
+            assert_eq!(
+                expand_error(&format!(
+                    "#[repr(C)]
+                    struct Vault {{
+                        authority_flag: {},
+                        #[pod(option)]
+                        authority: [u8; 32],
+                    }}",
+                    flag
+                )),
+                "`authority_flag` must be a `[u8; 4]` field"
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_attribute() {
+        for attribute in ["#[pod(f64)]", "#[pod]", "#[pod(u64, option)]", "#[pod = 1]"] {
+            assert_eq!(
+                expand_error(&format!(
+                    "#[repr(C)]
+                    struct Vault {{
+                        {}
+                        amount: [u8; 8],
+                    }}",
+                    attribute
+                )),
+                "expected `#[pod(option)]` or `#[pod(<integer type>)]`"
+            );
+        }
+
+        assert_eq!(
+            expand_error(
+                "#[repr(C)]
+                struct Vault {
+                    #[pod(u64)]
+                    amount: u64,
+                }"
+            ),
+            "integer accessors require a byte array field"
+        );
+    }
+
+    #[test]
+    fn test_invalid_struct() {
+        assert_eq!(
+            expand_error("#[repr(C)] struct Vault<T> { value: T }"),
+            "`Pod` cannot be derived for generic structs"
+        );
+        assert_eq!(
+            expand_error("struct Vault { value: [u8; 8] }"),
+            "`Pod` requires `#[repr(C)]`"
+        );
+        assert_eq!(
+            expand_error("#[repr(C)] struct Vault([u8; 8]);"),
+            "`Pod` can only be derived for structs with named fields"
+        );
+        assert_eq!(
+            expand_error("#[repr(C)] enum Vault { A }"),
+            "`Pod` can only be derived for structs"
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use pinocchio::{coption::COption, pubkey::Pubkey};
+use pinocchio_pod::Pod;
+
+#[derive(Pod)]
+#[repr(C)]
+struct Vault {
+    #[pod(u64)]
+    amount: [u8; 8],
+
+    #[pod(i16)]
+    delta: [u8; 2],
+
+    authority_flag: [u8; 4],
+
+    #[pod(option)]
+    authority: Pubkey,
+
+    bump: [u8; 1],
+}
+
+fn vault() -> Vault {
+    Vault {
+        amount: [0; 8],
+        delta: [0; 2],
+        authority_flag: [0; 4],
+        authority: [0; 32],
+        bump: [0; 1],
+    }
+}
+
+#[test]
+fn test_integer() {
+    let mut vault = vault();
+
+    vault.set_amount(u64::MAX - 1);
+    assert_eq!(vault.amount(), u64::MAX - 1);
+    assert_eq!(vault.amount, (u64::MAX - 1).to_le_bytes());
+
+    vault.set_delta(-2);
+    assert_eq!(vault.delta(), -2);
+    assert_eq!(vault.delta, (-2i16).to_le_bytes());
+}
+
+#[test]
+fn test_option() {
+    let mut vault = vault();
+    assert_eq!(vault.authority(), None);
+
+    vault.set_authority(Some(&[7; 32]));
+    assert_eq!(vault.authority(), Some(&[7; 32]));
+    assert_eq!(vault.authority_flag, [1, 0, 0, 0]);
+
+    vault.set_authority(None);
+    assert_eq!(vault.authority(), None);
+    assert_eq!(vault.authority_flag, [0; 4]);
+    assert_eq!(vault.authority, [0; 32]);
+}
+
+#[test]
+fn test_option_tag() {
+    let mut vault = vault();
+    vault.authority = [7; 32];
+
+    // only `[1, 0, 0, 0]` is a present value, matching `COption`
+    for flag in [[1, 1, 0, 0], [1, 0, 0, 1], [2, 0, 0, 0]] {
+        vault.authority_flag = flag;
+        assert_eq!(vault.authority(), None);
+    }
+
+    vault.authority_flag = [1, 0, 0, 0];
+    assert_eq!(vault.authority(), Some(&[7; 32]));
+}
+
+#[test]
+fn test_layout() {
+    assert_eq!(core::mem::size_of::<Vault>(), 47);
+    assert_eq!(core::mem::align_of::<Vault>(), 1);
+}
+
+#[derive(Pod)]
+#[repr(C)]
+struct Account {
+    #[pod(option)]
+    delegate: COption<Pubkey>,
+
+    delegate_copy_flag: [u8; 4],
+
+    #[pod(option)]
+    delegate_copy: Pubkey,
+}
+
+#[test]
+fn test_coption() {
+    let mut account = Account {
+        delegate: COption::none(),
+        delegate_copy_flag: [0; 4],
+        delegate_copy: [0; 32],
+    };
+    assert_eq!(core::mem::size_of::<Account>(), 72);
+    assert_eq!(account.delegate(), None);
+
+    account.set_delegate(Some(&[3; 32]));
+    assert_eq!(account.delegate(), Some(&[3; 32]));
+    assert!(account.delegate.is_some());
+
+    // both representations share the same layout and semantics
+    account.set_delegate_copy(Some(&[3; 32]));
+    let bytes = unsafe { core::slice::from_raw_parts(&account as *const Account as *const u8, 72) };
+    assert_eq!(bytes[..36], bytes[36..]);
+
+    account.set_delegate(None);
+    assert_eq!(account.delegate(), None);
+    assert_eq!(account.delegate.as_option(), None);
+}